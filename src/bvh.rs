@@ -0,0 +1,413 @@
+//Module pour l'accélération des intersections (boîtes englobantes et BVH)
+
+use crate::vec3::Vec3;
+use crate::ray::*;
+use crate::objects::Renderable;
+
+// Nombre maximal d'objets dans une feuille du BVH
+const MAX_LEAF_SIZE: usize = 4;
+// Coût d'un test de boîte par rapport au coût d'un test d'intersection avec un objet (heuristique SAH)
+const TRAVERSAL_COST: f64 = 0.5;
+// Marge ajoutée aux boîtes pour que les objets plats (plan, disque) aient une épaisseur non nulle
+const PADDING: f64 = 1e-4;
+
+// Boîte englobante alignée sur les axes
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Aabb {
+        let padding = Vec3::new(PADDING, PADDING, PADDING);
+        Aabb { min: min.min(&max) - padding, max: max.max(&min) + padding }
+    }
+
+    // Boîte vide, élément neutre de `union`
+    pub fn empty() -> Aabb {
+        Aabb {
+            min: Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Vec3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    // Boîte infinie, pour les objets non bornés
+    pub fn infinite() -> Aabb {
+        Aabb {
+            min: Vec3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb { min: self.min.min(&other.min), max: self.max.max(&other.max) }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z].iter().all(|v| v.is_finite())
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let d = self.max - self.min;
+        if d.x < 0.0 || d.y < 0.0 || d.z < 0.0 {
+            return 0.0;
+        }
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    // Distance d'entrée du rayon dans la boîte, si elle est touchée avant `max_distance`
+    pub fn hit(&self, ray: &Ray, inv_direction: Vec3, max_distance: f64) -> Option<f64> {
+        let mut tmin: f64 = 0.0;
+        let mut tmax = max_distance;
+
+        for (origin, inv, min, max) in [
+            (ray.origin.x, inv_direction.x, self.min.x, self.max.x),
+            (ray.origin.y, inv_direction.y, self.min.y, self.max.y),
+            (ray.origin.z, inv_direction.z, self.min.z, self.max.z),
+        ] {
+            let t1 = (min - origin) * inv;
+            let t2 = (max - origin) * inv;
+            // f64::min/max ignorent les NaN (rayon parallèle à une face et origine sur cette face)
+            tmin = tmin.max(t1.min(t2));
+            tmax = tmax.min(t1.max(t2));
+        }
+
+        if tmin <= tmax {
+            Some(tmin)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+enum BvhNode {
+    Leaf { bounds: Aabb, start: usize, count: usize },
+    Interior { bounds: Aabb, left: usize, right: usize },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bounds, .. } | BvhNode::Interior { bounds, .. } => bounds,
+        }
+    }
+}
+
+// Hiérarchie de boîtes englobantes, construite avec l'heuristique de surface (SAH).
+// Le BVH ne stocke que des indices : les objets restent dans le tableau d'origine.
+#[derive(Debug)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    indices: Vec<usize>,    // Indices des objets bornés, dans l'ordre des feuilles
+    unbounded: Vec<usize>,  // Objets sans boîte finie, testés pour chaque rayon
+}
+
+impl Bvh {
+    pub fn build<T: Renderable>(items: &[T]) -> Bvh {
+        let boxes: Vec<Aabb> = items.iter().map(|item| item.bounding_box()).collect();
+        Bvh::from_boxes(&boxes)
+    }
+
+    pub fn from_boxes(boxes: &[Aabb]) -> Bvh {
+        let (mut indices, unbounded): (Vec<usize>, Vec<usize>) = (0..boxes.len()).partition(|&i| boxes[i].is_finite());
+        let mut nodes = Vec::new();
+        if !indices.is_empty() {
+            let count = indices.len();
+            build_node(boxes, &mut indices, 0, count, &mut nodes);
+        }
+        Bvh { nodes, indices, unbounded }
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        if !self.unbounded.is_empty() {
+            return Aabb::infinite();
+        }
+        self.nodes.first().map(|node| *node.bounds()).unwrap_or_else(Aabb::empty)
+    }
+
+    // Intersection la plus proche ; `hit(i)` calcule l'intersection avec l'objet d'indice i
+    pub fn closest<F>(&self, ray: &Ray, mut hit: F) -> Option<Intersection>
+    where
+        F: FnMut(usize) -> Option<Intersection>,
+    {
+        let mut closest: Option<Intersection> = None;
+        let consider = |intersection: Option<Intersection>, closest: &mut Option<Intersection>| {
+            if let Some(intersection) = intersection {
+                if closest.as_ref().is_none_or(|c| intersection.distance < c.distance) {
+                    *closest = Some(intersection);
+                }
+            }
+        };
+
+        for &index in &self.unbounded {
+            consider(hit(index), &mut closest);
+        }
+
+        if self.nodes.is_empty() {
+            return closest;
+        }
+
+        let inv_direction = ray.direction.inverse();
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let max_distance = closest.as_ref().map_or(f64::INFINITY, |c| c.distance);
+            match &self.nodes[node_index] {
+                BvhNode::Leaf { bounds, start, count } => {
+                    if bounds.hit(ray, inv_direction, max_distance).is_none() {
+                        continue;
+                    }
+                    for &index in &self.indices[*start..*start + *count] {
+                        consider(hit(index), &mut closest);
+                    }
+                }
+                BvhNode::Interior { bounds, left, right } => {
+                    if bounds.hit(ray, inv_direction, max_distance).is_none() {
+                        continue;
+                    }
+                    // Parcourir d'abord l'enfant le plus proche, pour réduire rapidement max_distance
+                    let left_t = self.nodes[*left].bounds().hit(ray, inv_direction, max_distance);
+                    let right_t = self.nodes[*right].bounds().hit(ray, inv_direction, max_distance);
+                    match (left_t, right_t) {
+                        (Some(l), Some(r)) => {
+                            if l <= r {
+                                stack.push(*right);
+                                stack.push(*left);
+                            } else {
+                                stack.push(*left);
+                                stack.push(*right);
+                            }
+                        }
+                        (Some(_), None) => stack.push(*left),
+                        (None, Some(_)) => stack.push(*right),
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    // Vrai si un objet est touché à une distance inférieure à `max_distance` (rayons d'ombre)
+    pub fn any<F>(&self, ray: &Ray, max_distance: f64, mut hit: F) -> bool
+    where
+        F: FnMut(usize) -> Option<Intersection>,
+    {
+        let blocks = |intersection: Option<Intersection>| intersection.is_some_and(|i| i.distance < max_distance);
+
+        if self.unbounded.iter().any(|&index| blocks(hit(index))) {
+            return true;
+        }
+
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        let inv_direction = ray.direction.inverse();
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if node.bounds().hit(ray, inv_direction, max_distance).is_none() {
+                continue;
+            }
+            match node {
+                BvhNode::Leaf { start, count, .. } => {
+                    if self.indices[*start..*start + *count].iter().any(|&index| blocks(hit(index))) {
+                        return true;
+                    }
+                }
+                BvhNode::Interior { left, right, .. } => {
+                    stack.push(*left);
+                    stack.push(*right);
+                }
+            }
+        }
+
+        false
+    }
+    // Appelle `visit` pour chaque objet dont la boîte est traversée par le rayon, quelle que soit la distance
+    // (toutes les traversées d'un maillage, pour la CSG)
+    pub fn visit<F>(&self, ray: &Ray, mut visit: F)
+    where
+        F: FnMut(usize),
+    {
+        self.unbounded.iter().for_each(|&index| visit(index));
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let inv_direction = ray.direction.inverse();
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if node.bounds().hit(ray, inv_direction, f64::INFINITY).is_none() {
+                continue;
+            }
+            match node {
+                BvhNode::Leaf { start, count, .. } => self.indices[*start..*start + *count].iter().for_each(|&index| visit(index)),
+                BvhNode::Interior { left, right, .. } => {
+                    stack.push(*left);
+                    stack.push(*right);
+                }
+            }
+        }
+    }
+}
+
+// Construit récursivement le noeud contenant indices[start..start + count] et renvoie son indice
+fn build_node(boxes: &[Aabb], indices: &mut [usize], start: usize, count: usize, nodes: &mut Vec<BvhNode>) -> usize {
+    let slice = &mut indices[start..start + count];
+    let bounds = slice.iter().fold(Aabb::empty(), |acc, &i| acc.union(&boxes[i]));
+
+    let node_index = nodes.len();
+    nodes.push(BvhNode::Leaf { bounds, start, count });
+
+    if count <= MAX_LEAF_SIZE {
+        return node_index;
+    }
+
+    // Chercher, sur les trois axes, la coupe qui minimise le coût SAH
+    let leaf_cost = count as f64;
+    let parent_area = bounds.surface_area();
+    let mut best: Option<(usize, usize, f64)> = None; // (axe, nombre d'objets à gauche, coût)
+
+    for axis in 0..3 {
+        slice.sort_by(|&a, &b| axis_value(boxes[a].centroid(), axis).total_cmp(&axis_value(boxes[b].centroid(), axis)));
+
+        // Surfaces des boîtes des préfixes et des suffixes
+        let mut right_areas = vec![0.0; count];
+        let mut right_bounds = Aabb::empty();
+        for i in (1..count).rev() {
+            right_bounds = right_bounds.union(&boxes[slice[i]]);
+            right_areas[i] = right_bounds.surface_area();
+        }
+
+        let mut left_bounds = Aabb::empty();
+        for split in 1..count {
+            left_bounds = left_bounds.union(&boxes[slice[split - 1]]);
+            let cost = TRAVERSAL_COST
+                + (left_bounds.surface_area() * split as f64 + right_areas[split] * (count - split) as f64) / parent_area.max(f64::MIN_POSITIVE);
+            if best.is_none_or(|(_, _, best_cost)| cost < best_cost) {
+                best = Some((axis, split, cost));
+            }
+        }
+    }
+
+    let Some((axis, split, cost)) = best else { return node_index };
+    if cost >= leaf_cost && count <= 4 * MAX_LEAF_SIZE {
+        return node_index;
+    }
+
+    slice.sort_by(|&a, &b| axis_value(boxes[a].centroid(), axis).total_cmp(&axis_value(boxes[b].centroid(), axis)));
+
+    let left = build_node(boxes, indices, start, split, nodes);
+    let right = build_node(boxes, indices, start + split, count - split, nodes);
+    nodes[node_index] = BvhNode::Interior { bounds, left, right };
+
+    node_index
+}
+
+fn axis_value(v: Vec3, axis: usize) -> f64 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Material;
+    use crate::objects::*;
+    use crate::sampler::Sampler;
+
+    fn random_vec(sampler: &mut Sampler, extent: f64) -> Vec3 {
+        Vec3::new(sampler.next_f64() * 2.0 - 1.0, sampler.next_f64() * 2.0 - 1.0, sampler.next_f64() * 2.0 - 1.0) * extent
+    }
+
+    fn random_direction(sampler: &mut Sampler) -> Vec3 {
+        loop {
+            let direction = random_vec(sampler, 1.0);
+            if direction.length() > 0.1 {
+                return direction.normalize();
+            }
+        }
+    }
+
+    // Sphères et cubes tirés au hasard, et un plan infini sous la scène
+    fn scene(sampler: &mut Sampler) -> Vec<Object> {
+        let material = Material::default();
+        let mut objects: Vec<Object> = (0..40)
+            .map(|i| {
+                let center = random_vec(sampler, 20.0);
+                let size = 0.5 + sampler.next_f64() * 3.0;
+                if i % 2 == 0 {
+                    Object::Sphere(Sphere { center, radius: size, material })
+                } else {
+                    Object::Cube(Cube { center, length: size, material })
+                }
+            })
+            .collect();
+        objects.push(Object::Plane(Plane { point: Vec3::new(0.0, -25.0, 0.0), normal: Vec3::new(0.0, 1.0, 0.0), material }));
+        objects
+    }
+
+    fn linear_closest(objects: &[Object], ray: &Ray) -> Option<f64> {
+        objects.iter().filter_map(|object| object.intersect(ray)).map(|i| i.distance).min_by(f64::total_cmp)
+    }
+
+    // Compare le BVH à un parcours de tous les objets, pour le plus proche et pour les rayons d'ombre
+    fn check(objects: &[Object], bvh: &Bvh, ray: &Ray, sampler: &mut Sampler) {
+        let expected = linear_closest(objects, ray);
+        let found = bvh.closest(ray, |index| objects[index].intersect(ray)).map(|i| i.distance);
+        match (expected, found) {
+            (None, None) => {}
+            (Some(expected), Some(found)) => assert!((expected - found).abs() < 1e-9, "{:?} {:?}: expected {}, found {}", ray.origin, ray.direction, expected, found),
+            _ => panic!("{:?} {:?}: expected {:?}, found {:?}", ray.origin, ray.direction, expected, found),
+        }
+
+        for max_distance in [sampler.next_f64() * 50.0, expected.map_or(10.0, |t| t * 1.001), f64::INFINITY] {
+            let blocked = objects.iter().any(|object| object.intersect(ray).is_some_and(|i| i.distance < max_distance));
+            assert_eq!(bvh.any(ray, max_distance, |index| objects[index].intersect(ray)), blocked, "{:?} {:?}, max distance {}", ray.origin, ray.direction, max_distance);
+        }
+    }
+
+    #[test]
+    fn bvh_matches_a_linear_scan() {
+        let mut sampler = Sampler::new(42);
+        let objects = scene(&mut sampler);
+        let bvh = Bvh::build(&objects);
+        assert!(!bvh.bounds().is_finite());
+
+        for _ in 0..2000 {
+            let ray = Ray::new(random_vec(&mut sampler, 30.0), random_direction(&mut sampler));
+            check(&objects, &bvh, &ray, &mut sampler);
+        }
+    }
+
+    #[test]
+    fn rays_starting_inside_a_padded_box() {
+        // Origines dans la marge de la boîte d'un cube, juste à l'extérieur d'une face, ou à l'intérieur du cube
+        let mut sampler = Sampler::new(7);
+        let objects = scene(&mut sampler);
+        let bvh = Bvh::build(&objects);
+
+        for object in &objects {
+            let Object::Cube(cube) = object else { continue };
+            let half = cube.length / 2.0;
+            for offset in [half + PADDING / 2.0, half - PADDING / 2.0, 0.0] {
+                for _ in 0..50 {
+                    let ray = Ray::new(cube.center + Vec3::new(offset, 0.0, 0.0), random_direction(&mut sampler));
+                    check(&objects, &bvh, &ray, &mut sampler);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::ray::*;
 use crate::scene::*;
-use crate::objects::*;
+use crate::vec3::parse_vec3;
+use crate::sampler::Sampler;
 use std::ops::{Mul, Add};
 
 #[derive(Debug, Copy, Clone)]
@@ -52,6 +53,19 @@ impl Mul<f64> for Color {
     }
 }
 
+// Produit composante par composante (filtrage d'une lumière colorée par une surface)
+impl Mul<Color> for Color {
+    type Output = Color;
+
+    fn mul(self, rhs: Color) -> Color {
+        Color {
+            r: self.r * rhs.r,
+            g: self.g * rhs.g,
+            b: self.b * rhs.b,
+        }
+    }
+}
+
 impl Add for Color {
     type Output = Self;
 
@@ -65,7 +79,24 @@ impl Add for Color {
 }
 
 pub fn get_color(color_name: &str) -> Color {
-    match color_name.to_lowercase().as_str() {
+    parse_color(color_name).unwrap_or_else(|e| {
+        eprintln!("Warning: {}, defaulting to black.", e);
+        Color::new(0.0, 0.0, 0.0) // Couleur par défaut (noir) si la couleur est inconnue
+    })
+}
+
+// Comme get_color, mais renvoie une erreur pour une couleur inconnue.
+// Accepte aussi une couleur (r,g,b) avec des composantes entre 0 et 1.
+pub fn parse_color(color_name: &str) -> Result<Color, String> {
+    if color_name.trim().starts_with('(') {
+        let rgb = parse_vec3(color_name)?;
+        if [rgb.x, rgb.y, rgb.z].iter().any(|c| *c < 0.0) {
+            return Err(format!("Color components must not be negative in '{}'", color_name));
+        }
+        return Ok(Color::new(rgb.x, rgb.y, rgb.z));
+    }
+
+    let color = match color_name.trim().to_lowercase().as_str() {
         "red" => Color::new(1.0, 0.0, 0.0),
         "green" => Color::new(0.0, 1.0, 0.0),
         "blue" => Color::new(0.0, 0.0, 1.0),
@@ -78,85 +109,159 @@ pub fn get_color(color_name: &str) -> Color {
         "orange" => Color::new(1.0, 0.5, 0.0),
         "purple" => Color::new(0.5, 0.0, 0.5),
         "brown" => Color::new(0.6, 0.3, 0.0),
-        _ => {
-            eprintln!("Warning: Unknown color '{}', defaulting to black.", color_name);
-            Color::new(0.0, 0.0, 0.0) // Couleur par défaut (noir) si la couleur est inconnue
-        }
-    }
+        _ => return Err(format!("Unknown color '{}'", color_name)),
+    };
+    Ok(color)
 }
 
-pub fn color(ray: &Ray, scene: &SceneParams) -> Color {
-    if let Some(intersection) = scene.objects.iter()
-        .filter_map(|obj| obj.intersect(ray))
-        .min_by(|i1, i2| i1.distance.partial_cmp(&i2.distance).unwrap())
-    {
-        let final_color = compute_lighting(&intersection, &scene, ray, 0); // Couleur avec ombres
+// `sampler` fournit les points tirés sur les lumières surfaciques (ombres douces)
+pub fn color(ray: &Ray, scene: &SceneParams, sampler: &mut Sampler) -> Color {
+    if let Some(intersection) = scene.intersect(ray) {
+        let final_color = compute_lighting(&intersection, scene, ray, 0, sampler); // Couleur avec ombres
 
         return final_color;
     }
 
     // Retournez la couleur de fond si aucun objet n'est intersecté
-    scene.background_color.clone()
+    scene.background_color
+}
+
+// Couleur vue par un rayon secondaire, ou couleur de fond s'il ne touche rien
+fn trace(ray: &Ray, scene: &SceneParams, depth: u32, sampler: &mut Sampler) -> Color {
+    match scene.intersect(ray) {
+        Some(intersection) => compute_lighting(&intersection, scene, ray, depth, sampler),
+        None => scene.background_color,
+    }
+}
+
+// Approximation de Schlick du coefficient de réflexion de Fresnel.
+// `eta` est le rapport n1 / n2 des indices de réfraction de part et d'autre de la surface.
+pub fn schlick(cos_incident: f64, eta: f64) -> f64 {
+    let r0 = ((1.0 - eta) / (1.0 + eta)).powi(2);
+    // En sortant vers un milieu moins réfringent, il faut utiliser l'angle du rayon transmis
+    let cos = if eta > 1.0 {
+        let sin2_t = eta * eta * (1.0 - cos_incident * cos_incident);
+        if sin2_t > 1.0 {
+            return 1.0;
+        }
+        (1.0 - sin2_t).sqrt()
+    } else {
+        cos_incident
+    };
+    r0 + (1.0 - r0) * (1.0 - cos).powi(5)
 }
 
-fn compute_lighting(intersection: &Intersection, scene: &SceneParams, ray: &Ray, depth: u32) -> Color {
-    let mut final_color = scene.lights[0].color.scale(0.2);
+// Profondeur maximale de récursion pour les réflexions et la transparence
+const MAX_DEPTH: u32 = 6;
+// Décalage des rayons secondaires pour éviter l'auto-intersection
+pub const EPSILON: f64 = 1e-6;
+
+fn compute_lighting(intersection: &Intersection, scene: &SceneParams, ray: &Ray, depth: u32, sampler: &mut Sampler) -> Color {
+    let material = &intersection.material;
+    // Un objet émissif est visible même sans lumière
+    let mut final_color = scene.ambient_color + material.emission;
+
+    // Le rayon entre dans l'objet s'il arrive contre la normale ; les rayons secondaires partent du côté du rayon incident
+    let entering = ray.direction.dot(intersection.normal) < 0.0;
+    let facing_normal = if entering { intersection.normal } else { -intersection.normal };
 
     // Limiter la profondeur de récursion pour les réflexions
-    if depth >= 6 {
+    if depth >= MAX_DEPTH {
         return final_color;  // Si la profondeur maximale est atteinte, retourner la couleur actuelle
     }
 
     // Ajout d'une composante de lumière ambiante
     let ambient_intensity = 0.15;  
-    let ambient_color = intersection.color * ambient_intensity;
+    let ambient_color = material.diffuse * ambient_intensity;
     final_color = final_color + ambient_color;
 
+    // Une lumière surfacique est échantillonnée par plusieurs rayons d'ombre : la part de rayons bloqués donne la pénombre
     for light in &scene.lights {
-        // Vecteur de la lumière à l'intersection
-        let light_dir = (light.position - intersection.point).normalize();
+        for index in 0..light.samples {
+            // Direction de la lumière vue depuis l'intersection (aucun éclairage hors du cône d'un projecteur)
+            let Some(sample) = light.illuminate(intersection.point, index, sampler) else { continue };
+            let light_dir = sample.direction;
 
-        // Rayon d'ombre
-        let shadow_ray = Ray {
-            origin: intersection.point + intersection.normal * 1e-6, // Petit décalage pour éviter l'auto-intersection
-            direction: light_dir,
-        };
-
-        // Vérifier les intersections avec les objets de la scène
-        let in_shadow = scene.objects.iter()
-            .filter_map(|obj| obj.intersect(&shadow_ray))
-            .any(|shadow_intersection| shadow_intersection.distance < (light.position - intersection.point).length());
+            // Rayon d'ombre
+            let shadow_ray = Ray {
+                origin: intersection.point + facing_normal * EPSILON, // Petit décalage du côté du rayon incident pour éviter l'auto-intersection
+                direction: light_dir,
+            };
 
-        if !in_shadow {
-            // Produit scalaire entre la normale et le vecteur lumière
-            let diffuse_intensity = light_dir.dot(intersection.normal).max(0.0);
+            // Vérifier les intersections avec les objets de la scène
+            let in_shadow = scene.occluded(&shadow_ray, sample.distance);
 
-            // Calcul de la couleur diffuse
-            let diffuse_color = intersection.color * diffuse_intensity * light.intensity;
-            final_color = final_color + diffuse_color;
+            if in_shadow {
+                continue;
+            }
+            if let Some(microfacet) = &material.microfacet {
+                // Matériau à microfacettes : la BRDF donne à la fois la couleur diffuse et le reflet
+                final_color = final_color + microfacet.eval(facing_normal, -ray.direction.normalize(), light_dir) * sample.radiance;
+            } else {
+                // Produit scalaire entre la normale et le vecteur lumière
+                let diffuse_intensity = light_dir.dot(facing_normal).max(0.0);
 
-            // Ajouter la composante spéculaire pour les reflets
-            let reflection_dir = ray.direction.reflect(intersection.normal).normalize();
-            let reflection_ray = Ray {
-                origin: intersection.point + intersection.normal * 1e-6,
-                direction: reflection_dir,
-            };
+                // Calcul de la couleur diffuse
+                let diffuse_color = material.diffuse * sample.radiance * diffuse_intensity;
+                final_color = final_color + diffuse_color;
 
-            // Trouver l'objet le plus proche dans la direction de la réflexion
-            if let Some(reflection_intersection) = scene.objects.iter()
-                .filter_map(|obj| obj.intersect(&reflection_ray))
-                .min_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal)) 
-            {
-                let reflection_color = compute_lighting(&reflection_intersection, scene, &reflection_ray, depth + 1);
-                final_color = final_color + reflection_color * 0.5;  // Ajustez la force du reflet en fonction de votre besoin
+                // Composante spéculaire (Blinn-Phong) : le reflet est maximal quand la normale est alignée avec
+                // le vecteur médian entre la direction de la lumière et celle de l'observateur
+                if diffuse_intensity > 0.0 {
+                    let half_vector = (light_dir - ray.direction.normalize()).normalize();
+                    let specular_intensity = half_vector.dot(facing_normal).max(0.0).powf(material.shininess);
+                    final_color = final_color + material.specular * sample.radiance * specular_intensity;
+                }
             }
         }
     }
 
-    final_color
-}
+    // Reflet de la scène sur un matériau à microfacettes, d'autant plus net que la surface est lisse
+    if let Some(microfacet) = &material.microfacet {
+        let reflection_ray = Ray {
+            origin: intersection.point + facing_normal * EPSILON,
+            direction: ray.direction.reflect(facing_normal).normalize(),
+        };
+        let reflectance = microfacet.mirror_reflectance(-ray.direction.normalize().dot(facing_normal));
+        final_color = final_color + trace(&reflection_ray, scene, depth + 1, sampler) * reflectance;
+    }
 
+    // Réflexion miroir, pondérée par la réflectivité du matériau
+    if material.reflectivity > 0.0 {
+        let reflection_ray = Ray {
+            origin: intersection.point + facing_normal * EPSILON,
+            direction: ray.direction.reflect(facing_normal).normalize(),
+        };
+        final_color = final_color + trace(&reflection_ray, scene, depth + 1, sampler) * material.reflectivity;
+    }
+
+    // Matériau transparent (diélectrique) : réfraction selon la loi de Snell-Descartes,
+    // répartie entre réflexion et réfraction par l'approximation de Schlick
+    if material.transparency > 0.0 {
+        let reflection_ray = Ray {
+            origin: intersection.point + facing_normal * EPSILON,
+            direction: ray.direction.reflect(facing_normal).normalize(),
+        };
 
+        // Rapport des indices n1 / n2 : le rayon entre dans l'objet ou en sort
+        let eta = if entering { 1.0 / material.ior } else { material.ior };
+        let cos_incident = -ray.direction.dot(facing_normal);
 
+        let transmitted_color = match ray.direction.refract(facing_normal, eta) {
+            Some(refracted_dir) => {
+                let fresnel = schlick(cos_incident, eta);
+                let refraction_ray = Ray {
+                    origin: intersection.point - facing_normal * EPSILON,
+                    direction: refracted_dir,
+                };
+                trace(&reflection_ray, scene, depth + 1, sampler) * fresnel + trace(&refraction_ray, scene, depth + 1, sampler) * (1.0 - fresnel)
+            }
+            // Réflexion totale interne : toute la lumière est réfléchie
+            None => trace(&reflection_ray, scene, depth + 1, sampler),
+        };
 
+        final_color = final_color * (1.0 - material.transparency) + transmitted_color * material.transparency;
+    }
 
+    final_color
+}
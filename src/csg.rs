@@ -0,0 +1,539 @@
+//Module pour la géométrie de construction de solides (union, intersection, différence)
+
+use crate::vec3::*;
+use crate::ray::*;
+use crate::bvh::Aabb;
+use crate::material::Material;
+use crate::objects::*;
+use crate::primitives::*;
+use crate::solver::*;
+use crate::sdf::*;
+use crate::mesh::Mesh;
+
+// Nombre maximal de pas de la marche qui cherche toutes les traversées d'une surface SDF
+const SDF_SPAN_STEPS: usize = 4096;
+// Nombre de dichotomies pour situer une traversée entre deux pas
+const SDF_BISECTIONS: usize = 40;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference, // Le premier objet moins le second
+}
+
+impl CsgOperation {
+    pub fn from_name(name: &str) -> Result<CsgOperation, String> {
+        match name.trim().to_lowercase().as_str() {
+            "union" => Ok(CsgOperation::Union),
+            "intersection" => Ok(CsgOperation::Intersection),
+            "difference" => Ok(CsgOperation::Difference),
+            _ => Err(format!("Unknown CSG operation '{}', expected union, intersection or difference", name)),
+        }
+    }
+
+    // Vrai si un point à l'intérieur (ou non) de chacun des deux objets est à l'intérieur du résultat
+    fn contains(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+// Portion du rayon à l'intérieur d'un solide, entre le point d'entrée et le point de sortie.
+// Les distances peuvent être négatives (derrière l'origine du rayon) ou infinies (solide non borné) ;
+// les normales sont toujours orientées vers l'extérieur du solide.
+#[derive(Debug)]
+pub struct Span {
+    pub enter: Intersection,
+    pub exit: Intersection,
+}
+
+// Combinaison de deux solides ; les objets non fermés (quad, triangle, maillage ouvert...) ne peuvent pas en faire partie
+#[derive(Debug)]
+pub struct Csg {
+    pub operation: CsgOperation,
+    pub left: Object,
+    pub right: Object,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Object, right: Object) -> Csg {
+        Csg { operation, left, right }
+    }
+
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        combine(self.operation, self.left.spans(ray), self.right.spans(ray))
+    }
+}
+
+impl Renderable for Csg {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        // Première frontière du résultat devant l'origine du rayon, qu'elle soit une entrée ou une sortie
+        self.spans(ray).into_iter()
+            .flat_map(|span| [span.enter, span.exit])
+            .find(|hit| hit.distance > 0.0)
+            .filter(|hit| hit.distance.is_finite())
+    }
+
+    fn material(&self) -> Material {
+        self.left.material()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let left = self.left.bounding_box();
+        match self.operation {
+            CsgOperation::Union => left.union(&self.right.bounding_box()),
+            CsgOperation::Intersection => {
+                let right = self.right.bounding_box();
+                Aabb { min: left.min.max(&right.min), max: left.max.min(&right.max) }
+            }
+            CsgOperation::Difference => left,
+        }
+    }
+}
+
+// Combine deux listes d'intervalles triées en parcourant leurs frontières dans l'ordre du rayon
+pub fn combine(operation: CsgOperation, left: Vec<Span>, right: Vec<Span>) -> Vec<Span> {
+    let mut events = Vec::new();
+    for (spans, is_left) in [(left, true), (right, false)] {
+        for span in spans {
+            events.push((span.enter, is_left, true));
+            events.push((span.exit, is_left, false));
+        }
+    }
+    events.sort_by(|a, b| a.0.distance.total_cmp(&b.0.distance));
+
+    let (mut in_left, mut in_right) = (false, false);
+    let mut start: Option<Intersection> = None;
+    let mut result = Vec::new();
+    for (mut hit, is_left, entering) in events {
+        if is_left {
+            in_left = entering;
+        } else {
+            in_right = entering;
+            // La surface du second objet borde le résultat d'une différence de l'autre côté
+            if operation == CsgOperation::Difference {
+                hit.normal = -hit.normal;
+            }
+        }
+
+        let inside = operation.contains(in_left, in_right);
+        match (start.take(), inside) {
+            (None, true) => start = Some(hit),
+            (Some(enter), false) => result.push(Span { enter, exit: hit }),
+            (current, _) => start = current,
+        }
+    }
+    result
+}
+
+// Regroupe par paires des traversées de surface triées : entrée, sortie, entrée, sortie...
+// (valable pour une surface fermée ; une traversée isolée, rayon tangent, est ignorée)
+fn pair_hits(mut hits: Vec<Intersection>) -> Vec<Span> {
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    let mut hits = hits.into_iter();
+    let mut spans = Vec::new();
+    while let (Some(enter), Some(exit)) = (hits.next(), hits.next()) {
+        spans.push(Span { enter, exit });
+    }
+    spans
+}
+
+// Frontière à l'infini d'un solide non borné
+fn unbounded(distance: f64, material: Material) -> Intersection {
+    Intersection { point: Vec3::new(distance, distance, distance), normal: Vec3::new(0.0, 0.0, 0.0), distance, material }
+}
+
+// Distance à laquelle la droite du rayon traverse un disque (dans les deux sens)
+fn disk_crossing(disk: &Disk, ray: &Ray) -> Option<f64> {
+    let denom = disk.normal.dot(ray.direction);
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+    let t = (disk.center - ray.origin).dot(disk.normal) / denom;
+    ((ray.at(t) - disk.center).length() <= disk.radius).then_some(t)
+}
+
+impl Object {
+    // Vrai pour les objets qui délimitent un volume, seuls utilisables dans une opération CSG
+    pub fn is_solid(&self) -> bool {
+        match self {
+            Object::Sphere(_) | Object::Cube(_) | Object::Torus(_) | Object::Capsule(_) | Object::Plane(_) | Object::Sdf(_) | Object::Csg(_) => true,
+            Object::Cylinder(cylinder) => cylinder.capped || !cylinder.height.is_finite(),
+            Object::Cone(cone) => cone.capped,
+            Object::Mesh(mesh) => mesh.is_closed(),
+            Object::Transformed(transformed) => transformed.object.is_solid(),
+            Object::Group(group) => group.objects.iter().all(|object| object.is_solid()),
+            Object::FlatePlane(_) | Object::Quad(_) | Object::Triangle(_) | Object::Heightfield(_) => false,
+        }
+    }
+
+    // Intervalles du rayon à l'intérieur de l'objet, triés (vide pour un objet qui n'est pas un solide)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        match self {
+            Object::Sphere(sphere) => sphere.spans(ray),
+            Object::Cube(cube) => cube.spans(ray),
+            Object::Cylinder(cylinder) => cylinder.spans(ray),
+            Object::Cone(cone) => cone.spans(ray),
+            Object::Torus(torus) => torus.spans(ray),
+            Object::Capsule(capsule) => capsule.spans(ray),
+            Object::Plane(plane) => plane.spans(ray),
+            Object::Mesh(mesh) => mesh.spans(ray),
+            Object::Sdf(sdf) => sdf.spans(ray),
+            Object::Csg(csg) => csg.spans(ray),
+            Object::Transformed(transformed) => transformed.spans(ray),
+            Object::Group(group) => group.objects.iter()
+                .fold(Vec::new(), |spans, object| combine(CsgOperation::Union, spans, object.spans(ray))),
+            Object::FlatePlane(_) | Object::Quad(_) | Object::Triangle(_) | Object::Heightfield(_) => Vec::new(),
+        }
+    }
+}
+
+impl Sphere {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        let oc = ray.origin - self.center;
+        let roots = solve_quadratic(ray.direction.dot(ray.direction), 2.0 * oc.dot(ray.direction), oc.dot(oc) - self.radius * self.radius);
+        pair_hits(roots.into_iter().map(|t| {
+            let point = ray.at(t);
+            Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }
+        }).collect())
+    }
+}
+
+impl Cube {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        let half_length = self.length / 2.0;
+        let mut tmin = f64::NEG_INFINITY;
+        let mut tmax = f64::INFINITY;
+        for (origin, direction, center) in [
+            (ray.origin.x, ray.direction.x, self.center.x),
+            (ray.origin.y, ray.direction.y, self.center.y),
+            (ray.origin.z, ray.direction.z, self.center.z),
+        ] {
+            let t1 = (center - half_length - origin) / direction;
+            let t2 = (center + half_length - origin) / direction;
+            tmin = tmin.max(t1.min(t2));
+            tmax = tmax.min(t1.max(t2));
+        }
+        if tmin > tmax || !tmin.is_finite() || !tmax.is_finite() {
+            return Vec::new();
+        }
+
+        let hit = |t: f64| {
+            let point = ray.at(t);
+            Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }
+        };
+        vec![Span { enter: hit(tmin), exit: hit(tmax) }]
+    }
+}
+
+impl Cylinder {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        let oc = ray.origin - self.center;
+        let d = ray.direction;
+        let a = d.x * d.x + d.z * d.z;
+        let c = oc.x * oc.x + oc.z * oc.z - self.radius * self.radius;
+
+        // Rayon parallèle à l'axe d'un cylindre infini : à l'intérieur sur toute sa longueur, ou jamais
+        if !self.height.is_finite() && a < 1e-12 {
+            return if c < 0.0 {
+                vec![Span { enter: unbounded(f64::NEG_INFINITY, self.material), exit: unbounded(f64::INFINITY, self.material) }]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let mut hits: Vec<Intersection> = solve_quadratic(a, 2.0 * (oc.x * d.x + oc.z * d.z), c).into_iter()
+            .map(|t| (t, ray.at(t)))
+            .filter(|&(_, point)| self.height.is_infinite() || (point.y >= self.center.y && point.y <= self.center.y + self.height))
+            .map(|(t, point)| Intersection { point, normal: self.normal_at(point), distance: t, material: self.material })
+            .collect();
+        if self.capped {
+            for disk in [&self.top_disk, &self.bottom_disk] {
+                if let Some(t) = disk_crossing(disk, ray) {
+                    hits.push(Intersection { point: ray.at(t), normal: disk.normal, distance: t, material: self.material });
+                }
+            }
+        }
+        pair_hits(hits)
+    }
+}
+
+impl Cone {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        // Traversées de la surface latérale, devant et derrière l'origine du rayon
+        let mut hits: Vec<Intersection> = self.side_roots(ray).into_iter()
+            .map(|t| {
+                let point = ray.at(t);
+                Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }
+            })
+            .collect();
+        for disk in std::iter::once(&self.bottom_disk).chain(&self.top_disk) {
+            if let Some(t) = disk_crossing(disk, ray) {
+                hits.push(Intersection { point: ray.at(t), normal: disk.normal, distance: t, material: self.material });
+            }
+        }
+        pair_hits(hits)
+    }
+}
+
+impl Torus {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        pair_hits(self.roots(ray).into_iter().map(|t| {
+            let point = ray.at(t);
+            Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }
+        }).collect())
+    }
+}
+
+impl Capsule {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        pair_hits(self.roots(ray).into_iter().map(|t| {
+            let point = ray.at(t);
+            Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }
+        }).collect())
+    }
+}
+
+impl Plane {
+    // Le solide est le demi-espace situé du côté opposé à la normale
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        let denom = self.normal.dot(ray.direction);
+        let below = (ray.origin - self.point).dot(self.normal) < 0.0;
+        let (negative, positive) = (unbounded(f64::NEG_INFINITY, self.material), unbounded(f64::INFINITY, self.material));
+
+        if denom.abs() < 1e-12 {
+            return if below { vec![Span { enter: negative, exit: positive }] } else { Vec::new() };
+        }
+        let t = (self.point - ray.origin).dot(self.normal) / denom;
+        let hit = Intersection { point: ray.at(t), normal: self.normal, distance: t, material: self.material };
+        if denom < 0.0 {
+            vec![Span { enter: hit, exit: positive }]
+        } else {
+            vec![Span { enter: negative, exit: hit }]
+        }
+    }
+}
+
+impl Mesh {
+    // Valable pour un maillage fermé ; les normales sont réorientées vers l'extérieur, quel que soit le sens des faces
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        pair_hits(self.crossings(ray)).into_iter()
+            .map(|Span { mut enter, mut exit }| {
+                if enter.normal.dot(ray.direction) > 0.0 {
+                    enter.normal = -enter.normal;
+                }
+                if exit.normal.dot(ray.direction) < 0.0 {
+                    exit.normal = -exit.normal;
+                }
+                Span { enter, exit }
+            })
+            .collect()
+    }
+}
+
+impl Sdf {
+    // Marche le long de toute la droite du rayon dans la boîte englobante, par pas bornés par la distance à la surface,
+    // en notant chaque changement de signe de la fonction de distance (situé ensuite par dichotomie)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        let length = ray.direction.length();
+        let direction = ray.direction / length;
+        let origin = ray.origin - self.center;
+
+        let (mut start, mut end) = (-MAX_DISTANCE, MAX_DISTANCE);
+        for (o, d, min, max) in [
+            (origin.x, direction.x, self.bounds.min.x, self.bounds.max.x),
+            (origin.y, direction.y, self.bounds.min.y, self.bounds.max.y),
+            (origin.z, direction.z, self.bounds.min.z, self.bounds.max.z),
+        ] {
+            let (t1, t2) = ((min - o) / d, (max - o) / d);
+            start = start.max(t1.min(t2));
+            end = end.min(t1.max(t2));
+        }
+        if start > end {
+            return Vec::new();
+        }
+
+        let distance = |s: f64| self.root.distance(origin + direction * s);
+        let crossing = |s: f64| {
+            let t = s / length;
+            let point = ray.at(t);
+            Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }
+        };
+
+        // Une forme répétée à l'infini peut contenir les extrémités de la partie parcourue
+        let mut current = distance(start);
+        let mut enter = (current < 0.0).then(|| unbounded(f64::NEG_INFINITY, self.material));
+        let mut spans = Vec::new();
+        let mut s = start;
+        for _ in 0..SDF_SPAN_STEPS {
+            if s >= end {
+                break;
+            }
+            let next = (s + current.abs().max(HIT_EPSILON)).min(end);
+            let next_distance = distance(next);
+            if (next_distance < 0.0) != (current < 0.0) {
+                let (mut low, mut high) = (s, next);
+                for _ in 0..SDF_BISECTIONS {
+                    let middle = (low + high) / 2.0;
+                    if (distance(middle) < 0.0) == (current < 0.0) { low = middle } else { high = middle }
+                }
+                let hit = crossing((low + high) / 2.0);
+                match enter.take() {
+                    None => enter = Some(hit),
+                    Some(enter) => spans.push(Span { enter, exit: hit }),
+                }
+            }
+            (s, current) = (next, next_distance);
+        }
+        if let Some(enter) = enter {
+            spans.push(Span { enter, exit: unbounded(f64::INFINITY, self.material) });
+        }
+        spans
+    }
+}
+
+impl Transformed {
+    pub fn spans(&self, ray: &Ray) -> Vec<Span> {
+        let local_ray = self.transform.inverse_ray(ray);
+        let to_world = |hit: Intersection| {
+            if !hit.distance.is_finite() {
+                return hit;
+            }
+            Intersection { point: ray.at(hit.distance), normal: self.transform.normal(hit.normal), ..hit }
+        };
+        self.object.spans(&local_ray).into_iter()
+            .map(|span| Span { enter: to_world(span.enter), exit: to_world(span.exit) })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn sphere(x: f64, radius: f64) -> Object {
+        Object::Sphere(Sphere { center: Vec3::new(x, 0.0, 0.0), radius, material: Material::default() })
+    }
+
+    // Distances d'entrée et de sortie des intervalles d'un rayon partant de x = -5 dans la direction +x
+    fn spans(operation: CsgOperation, left: Object, right: Object) -> Vec<(f64, f64)> {
+        let ray = Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        Csg::new(operation, left, right).spans(&ray).iter().map(|span| (span.enter.distance, span.exit.distance)).collect()
+    }
+
+    fn assert_spans(found: Vec<(f64, f64)>, expected: &[(f64, f64)]) {
+        assert_eq!(found.len(), expected.len(), "expected {:?}, found {:?}", expected, found);
+        for (&(enter, exit), &(expected_enter, expected_exit)) in found.iter().zip(expected) {
+            assert!((enter - expected_enter).abs() < 1e-9 && (exit - expected_exit).abs() < 1e-9, "expected {:?}, found {:?}", expected, found);
+        }
+    }
+
+    #[test]
+    fn union_of_overlapping_spheres_is_one_span() {
+        assert_spans(spans(CsgOperation::Union, sphere(0.0, 1.0), sphere(1.0, 1.0)), &[(4.0, 7.0)]);
+    }
+
+    #[test]
+    fn union_of_separate_spheres_keeps_both_spans() {
+        assert_spans(spans(CsgOperation::Union, sphere(0.0, 1.0), sphere(4.0, 1.0)), &[(4.0, 6.0), (8.0, 10.0)]);
+    }
+
+    #[test]
+    fn intersection_keeps_the_overlap() {
+        assert_spans(spans(CsgOperation::Intersection, sphere(0.0, 1.0), sphere(1.0, 1.0)), &[(5.0, 6.0)]);
+        assert_spans(spans(CsgOperation::Intersection, sphere(0.0, 1.0), sphere(4.0, 1.0)), &[]);
+    }
+
+    #[test]
+    fn difference_exits_through_the_flipped_second_surface() {
+        let ray = Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let result = Csg::new(CsgOperation::Difference, sphere(0.0, 1.0), sphere(1.0, 1.0)).spans(&ray);
+        assert_eq!(result.len(), 1);
+        assert!((result[0].enter.distance - 4.0).abs() < 1e-9);
+        assert!((result[0].exit.distance - 5.0).abs() < 1e-9);
+        // La normale du second objet est retournée : elle pointe vers l'extérieur du résultat
+        assert!((result[0].exit.normal - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn difference_with_inner_sphere_leaves_a_shell() {
+        assert_spans(spans(CsgOperation::Difference, sphere(0.0, 2.0), sphere(0.0, 1.0)), &[(3.0, 4.0), (6.0, 7.0)]);
+    }
+
+    #[test]
+    fn intersect_from_inside_returns_the_exit() {
+        let csg = Csg::new(CsgOperation::Union, sphere(0.0, 1.0), sphere(1.0, 1.0));
+        let intersection = csg.intersect(&Ray::new(Vec3::new(0.5, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0))).unwrap();
+        assert!((intersection.distance - 1.5).abs() < 1e-9);
+        assert!((intersection.normal - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-9);
+    }
+
+    fn sdf_sphere(radius: f64) -> Sdf {
+        Sdf::new(Arc::new(SdfNode::Sphere { center: Vec3::new(0.0, 0.0, 0.0), radius }), Vec3::new(0.0, 0.0, 0.0), Material::default())
+    }
+
+    // Cube [-1, 1]³ en 12 triangles, dans le sens trigonométrique vu de l'extérieur (ou l'inverse)
+    fn cube_mesh(reversed: bool, faces: usize) -> Mesh {
+        let vertices = (0..8).map(|i| Vec3::new(if i & 1 == 0 { -1.0 } else { 1.0 }, if i & 2 == 0 { -1.0 } else { 1.0 }, if i & 4 == 0 { -1.0 } else { 1.0 })).collect();
+        let quads = [[0, 4, 6, 2], [1, 3, 7, 5], [0, 1, 5, 4], [2, 6, 7, 3], [0, 2, 3, 1], [4, 5, 7, 6]];
+        let indices = quads.iter()
+            .flat_map(|&[a, b, c, d]| [[a, b, c], [a, c, d]])
+            .map(|[a, b, c]| if reversed { [a, c, b] } else { [a, b, c] })
+            .take(faces)
+            .collect();
+        Mesh::new(vertices, None, indices, Material::default()).unwrap()
+    }
+
+    fn distances(spans: Vec<Span>) -> Vec<(f64, f64)> {
+        spans.iter().map(|span| (span.enter.distance, span.exit.distance)).collect()
+    }
+
+    #[test]
+    fn sdf_spans_match_the_sphere() {
+        let sdf = sdf_sphere(1.0);
+        assert_spans(distances(sdf.spans(&Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)))), &[(4.0, 6.0)]);
+        // Depuis le centre, l'entrée est derrière l'origine ; la longueur de la direction est prise en compte
+        let spans = sdf.spans(&Ray::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0)));
+        assert_spans(distances(spans), &[(-0.5, 0.5)]);
+        assert!(sdf.spans(&Ray::new(Vec3::new(-5.0, 1.5, 0.0), Vec3::new(1.0, 0.0, 0.0))).is_empty());
+    }
+
+    #[test]
+    fn sdf_can_be_subtracted() {
+        let sdf = Object::Sdf(Box::new(sdf_sphere(1.0)));
+        assert!(sdf.is_solid());
+        assert_spans(spans(CsgOperation::Difference, sphere(0.0, 2.0), sdf), &[(3.0, 4.0), (6.0, 7.0)]);
+    }
+
+    #[test]
+    fn closed_mesh_spans() {
+        let mesh = cube_mesh(false, 12);
+        assert!(mesh.is_closed());
+        assert_spans(distances(mesh.spans(&Ray::new(Vec3::new(-5.0, 0.2, 0.3), Vec3::new(1.0, 0.0, 0.0)))), &[(4.0, 6.0)]);
+        // Le rayon passe par des arêtes : chaque traversée n'est comptée qu'une fois
+        let spans = mesh.spans(&Ray::new(Vec3::new(-5.0, -5.0, 0.0), Vec3::new(1.0, 1.0, 0.0)));
+        assert_spans(distances(spans), &[(4.0, 6.0)]);
+    }
+
+    #[test]
+    fn mesh_normals_point_outwards_whatever_the_winding() {
+        let ray = Ray::new(Vec3::new(-5.0, 0.2, 0.3), Vec3::new(1.0, 0.0, 0.0));
+        for reversed in [false, true] {
+            let spans = cube_mesh(reversed, 12).spans(&ray);
+            assert!((spans[0].enter.normal - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-9);
+            assert!((spans[0].exit.normal - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn open_mesh_is_not_a_solid() {
+        let mesh = cube_mesh(false, 11);
+        assert!(!mesh.is_closed());
+        assert!(!Object::Mesh(Box::new(mesh)).is_solid());
+    }
+}
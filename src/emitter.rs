@@ -0,0 +1,211 @@
+//Module pour les objets lumineux (matériaux émissifs) : leur surface est échantillonnée comme une lumière
+
+use std::f64::consts::PI;
+use crate::vec3::*;
+use crate::color::*;
+use crate::ray::*;
+use crate::objects::*;
+use crate::light::LightSample;
+use crate::material::Material;
+use crate::transform::Transform;
+
+// Les rayons d'ombre s'arrêtent un peu avant la surface émettrice, pour ne pas la prendre pour un obstacle
+const SHADOW_MARGIN: f64 = 1e-6;
+
+// Morceau de surface émettrice, dont les points sont tirés uniformément selon l'aire
+#[derive(Debug, Clone)]
+pub enum Patch {
+    Sphere { center: Vec3, radius: f64 },
+    Parallelogram { corner: Vec3, u: Vec3, v: Vec3 },
+    Triangle { a: Vec3, b: Vec3, c: Vec3 },
+}
+
+impl Patch {
+    pub fn area(&self) -> f64 {
+        match self {
+            Patch::Sphere { radius, .. } => 4.0 * PI * radius * radius,
+            Patch::Parallelogram { u, v, .. } => u.cross(*v).length(),
+            Patch::Triangle { a, b, c } => (*b - *a).cross(*c - *a).length() / 2.0,
+        }
+    }
+
+    // Même morceau placé par une transformation affine. Une sphère n'est échantillonnée que si la transformation
+    // conserve sa forme (même facteur d'échelle selon chaque axe).
+    pub fn transformed(&self, transform: &Transform) -> Result<Patch, String> {
+        Ok(match self {
+            Patch::Sphere { center, radius } => {
+                let axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)].map(|axis| transform.vector(axis));
+                let scale = axes[0].length();
+                let uniform = axes.iter().all(|axis| (axis.length() - scale).abs() <= 1e-9 * scale)
+                    && (0..3).all(|i| axes[i].dot(axes[(i + 1) % 3]).abs() <= 1e-9 * scale * scale);
+                if !uniform {
+                    return Err("An emissive sphere must be scaled by the same factor along each axis".to_string());
+                }
+                Patch::Sphere { center: transform.point(*center), radius: radius * scale }
+            }
+            Patch::Parallelogram { corner, u, v } => Patch::Parallelogram { corner: transform.point(*corner), u: transform.vector(*u), v: transform.vector(*v) },
+            Patch::Triangle { a, b, c } => Patch::Triangle { a: transform.point(*a), b: transform.point(*b), c: transform.point(*c) },
+        })
+    }
+
+    // Point de la surface et normale en ce point, pour des nombres aléatoires dans [0, 1[²
+    pub fn sample(&self, random: (f64, f64)) -> (Vec3, Vec3) {
+        let (s1, s2) = random;
+        match self {
+            Patch::Sphere { center, radius } => {
+                let z = 1.0 - 2.0 * s1;
+                let r = (1.0 - z * z).max(0.0).sqrt();
+                let phi = 2.0 * PI * s2;
+                let normal = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+                (*center + normal * *radius, normal)
+            }
+            Patch::Parallelogram { corner, u, v } => (*corner + *u * s1 + *v * s2, u.cross(*v).normalize()),
+            Patch::Triangle { a, b, c } => {
+                let root = s1.sqrt();
+                let (wb, wc) = (root * (1.0 - s2), root * s2);
+                (*a + (*b - *a) * wb + (*c - *a) * wc, (*b - *a).cross(*c - *a).normalize())
+            }
+        }
+    }
+}
+
+// Surface émettrice d'un objet de la scène. Les deux faces d'une surface plane émettent.
+#[derive(Debug)]
+pub struct Emitter {
+    pub object: usize,                 // Indice de l'objet dans la scène
+    pub patches: Vec<(Patch, Color)>,  // Morceaux de surface et leur émission
+    pub cumulative_areas: Vec<f64>,    // Aires cumulées des morceaux, pour en choisir un selon son aire
+    pub area: f64,
+    pub samples: u32,                  // Rayons d'ombre par point éclairé (le plus grand des matériaux émissifs)
+}
+
+impl Emitter {
+    // Surface émettrice d'un objet de la scène, ou None s'il n'a aucun matériau émissif.
+    // Les sphères, cubes, quads, plans bornés, triangles et maillages sont échantillonnés, y compris transformés ou
+    // dans une instance de groupe ; les autres formes émissives sont refusées.
+    pub fn from_object(object: &Object, index: usize) -> Result<Option<Emitter>, String> {
+        let mut patches = Vec::new();
+        let mut samples = 0;
+        collect_patches(object, &Transform::identity(), &mut patches, &mut samples)?;
+
+        let patches: Vec<(Patch, Color)> = patches.into_iter().filter(|(patch, _)| patch.area() > 0.0).collect();
+        if patches.is_empty() {
+            return Ok(None);
+        }
+        let cumulative_areas: Vec<f64> = patches.iter()
+            .scan(0.0, |total, (patch, _)| {
+                *total += patch.area();
+                Some(*total)
+            })
+            .collect();
+        let area = cumulative_areas[cumulative_areas.len() - 1];
+        Ok(Some(Emitter { object: index, patches, cumulative_areas, area, samples }))
+    }
+
+    // Éclairage reçu au point donné depuis un point de la surface tiré uniformément selon l'aire
+    pub fn sample(&self, point: Vec3, random: (f64, f64)) -> Option<LightSample> {
+        // Le premier nombre aléatoire choisit le morceau, puis est ramené dans [0, 1[ pour tirer le point
+        let target = random.0 * self.area;
+        let index = self.cumulative_areas.partition_point(|&total| total <= target).min(self.patches.len() - 1);
+        let start = if index == 0 { 0.0 } else { self.cumulative_areas[index - 1] };
+        let (patch, emission) = &self.patches[index];
+        let s1 = ((target - start) / patch.area()).clamp(0.0, 1.0 - f64::EPSILON);
+
+        let (on_light, normal) = patch.sample((s1, random.1));
+        let distance = (on_light - point).length();
+        let direction = (on_light - point) / distance;
+        let cos_light = direction.dot(normal).abs();
+        if distance == 0.0 || cos_light < 1e-9 {
+            return None;
+        }
+
+        // Densité par angle solide ; l'éclairage est celui d'une luminance `emission` vue sous cet angle
+        let pdf = distance * distance / (cos_light * self.area);
+        Some(LightSample { direction, distance: distance * (1.0 - SHADOW_MARGIN), radiance: *emission * (1.0 / (PI * pdf)), pdf })
+    }
+
+    // Densité (par angle solide) avec laquelle `sample` aurait choisi le point touché depuis `origin`
+    pub fn pdf(&self, origin: Vec3, intersection: &Intersection) -> f64 {
+        let offset = intersection.point - origin;
+        let distance = offset.length();
+        let cos_light = (offset / distance).dot(intersection.normal).abs();
+        if cos_light < 1e-9 {
+            return 0.0;
+        }
+        distance * distance / (cos_light * self.area)
+    }
+
+    // Vrai si l'intersection du rayon avec la scène est sur cet objet
+    pub fn is_hit(&self, objects: &[Object], ray: &Ray, intersection: &Intersection) -> bool {
+        objects[self.object].intersect(ray).is_some_and(|hit| hit.distance == intersection.distance)
+    }
+}
+
+// Ajoute les morceaux émissifs de l'objet, placés par `transform`, et retient le plus grand nombre d'échantillons demandé
+fn collect_patches(object: &Object, transform: &Transform, patches: &mut Vec<(Patch, Color)>, samples: &mut u32) -> Result<(), String> {
+    let mut add = |patch: Patch, material: &Material| {
+        if is_emissive(&material.emission) {
+            patches.push((patch.transformed(transform)?, material.emission));
+            *samples = (*samples).max(material.emission_samples);
+        }
+        Ok::<(), String>(())
+    };
+    match object {
+        Object::Sphere(sphere) => add(Patch::Sphere { center: sphere.center, radius: sphere.radius }, &sphere.material)?,
+        Object::Cube(cube) => {
+            let (half, axes) = (cube.length / 2.0, [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)]);
+            for axis in 0..3 {
+                let (normal, u, v) = (axes[axis], axes[(axis + 1) % 3], axes[(axis + 2) % 3]);
+                for side in [-1.0, 1.0] {
+                    let corner = cube.center + normal * (side * half) - u * half - v * half;
+                    add(Patch::Parallelogram { corner, u: u * cube.length, v: v * cube.length }, &cube.material)?;
+                }
+            }
+        }
+        Object::Quad(quad) => add(Patch::Parallelogram { corner: quad.corner, u: quad.u, v: quad.v }, &quad.material)?,
+        Object::FlatePlane(plane) => {
+            let (width_axis, height_axis) = plane.axes();
+            let (u, v) = (width_axis * plane.width, height_axis * plane.heigth);
+            add(Patch::Parallelogram { corner: plane.center - u / 2.0 - v / 2.0, u, v }, &plane.material)?;
+        }
+        Object::Triangle(triangle) => add(Patch::Triangle { a: triangle.a, b: triangle.b, c: triangle.c }, &triangle.material)?,
+        Object::Mesh(mesh) => {
+            for face in 0..mesh.indices.len() {
+                let [a, b, c] = mesh.indices[face];
+                add(Patch::Triangle { a: mesh.vertices[a], b: mesh.vertices[b], c: mesh.vertices[c] }, &mesh.face_material(face))?;
+            }
+        }
+        Object::Transformed(transformed) => collect_patches(&transformed.object, &(*transform * transformed.transform), patches, samples)?,
+        Object::Group(group) => {
+            for object in &group.objects {
+                collect_patches(object, transform, patches, samples)?;
+            }
+        }
+        Object::Csg(csg) => {
+            if has_emission(&csg.left) || has_emission(&csg.right) {
+                return Err("Emissive materials cannot be used in a csg section".to_string());
+            }
+        }
+        _ => {
+            if is_emissive(&object.material().emission) {
+                return Err("Emissive materials are only supported on spheres, cubes, quads, flateplanes, triangles and obj models".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Vrai si une partie de l'objet a un matériau émissif
+fn has_emission(object: &Object) -> bool {
+    match object {
+        Object::Mesh(mesh) => (0..mesh.indices.len()).any(|face| is_emissive(&mesh.face_material(face).emission)),
+        Object::Transformed(transformed) => has_emission(&transformed.object),
+        Object::Group(group) => group.objects.iter().any(has_emission),
+        Object::Csg(csg) => has_emission(&csg.left) || has_emission(&csg.right),
+        _ => is_emissive(&object.material().emission),
+    }
+}
+
+pub fn is_emissive(emission: &Color) -> bool {
+    emission.r > 0.0 || emission.g > 0.0 || emission.b > 0.0
+}
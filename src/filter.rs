@@ -0,0 +1,63 @@
+//Module pour les filtres de reconstruction de l'image (anti-crénelage)
+
+// Filtre appliqué aux échantillons d'un pixel : chaque échantillon est pondéré selon sa distance au centre du pixel
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PixelFilter {
+    Box,
+    Tent,
+    Gaussian,
+    Mitchell,
+}
+
+impl PixelFilter {
+    pub fn from_name(name: &str) -> Result<PixelFilter, String> {
+        match name.trim().to_lowercase().as_str() {
+            "box" => Ok(PixelFilter::Box),
+            "tent" => Ok(PixelFilter::Tent),
+            "gaussian" => Ok(PixelFilter::Gaussian),
+            "mitchell" => Ok(PixelFilter::Mitchell),
+            _ => Err(format!("Unknown pixel filter '{}', expected box, tent, gaussian or mitchell", name)),
+        }
+    }
+
+    // Rayon du filtre, en pixels : les échantillons sont tirés dans [-radius, radius]² autour du centre du pixel
+    pub fn radius(&self) -> f64 {
+        match self {
+            PixelFilter::Box => 0.5,
+            PixelFilter::Tent => 1.0,
+            PixelFilter::Gaussian => 1.5,
+            PixelFilter::Mitchell => 2.0,
+        }
+    }
+
+    // Poids d'un échantillon décalé de (dx, dy) pixels par rapport au centre du pixel
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.weight_1d(dx) * self.weight_1d(dy)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let radius = self.radius();
+        let x = x.abs();
+        if x > radius {
+            return 0.0;
+        }
+        match self {
+            PixelFilter::Box => 1.0,
+            PixelFilter::Tent => 1.0 - x / radius,
+            PixelFilter::Gaussian => {
+                // Gaussienne décalée pour s'annuler au bord du filtre
+                let alpha = 2.0;
+                ((-alpha * x * x).exp() - (-alpha * radius * radius).exp()).max(0.0)
+            }
+            PixelFilter::Mitchell => {
+                // Filtre de Mitchell-Netravali avec B = C = 1/3 (lobes négatifs qui accentuent les contours)
+                let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+                if x < 1.0 {
+                    ((12.0 - 9.0 * b - 6.0 * c) * x * x * x + (-18.0 + 12.0 * b + 6.0 * c) * x * x + (6.0 - 2.0 * b)) / 6.0
+                } else {
+                    ((-b - 6.0 * c) * x * x * x + (6.0 * b + 30.0 * c) * x * x + (-12.0 * b - 48.0 * c) * x + (8.0 * b + 24.0 * c)) / 6.0
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//Module pour les groupes d'objets (graphe de scène et instances)
+
+use std::sync::Arc;
+use crate::vec3::Vec3;
+use crate::ray::*;
+use crate::bvh::{Aabb, Bvh};
+use crate::material::Material;
+use crate::objects::*;
+use crate::transform::*;
+use crate::utils::Params;
+
+// Groupe nommé d'objets, exprimés dans le repère du groupe.
+// Un groupe est partagé (Arc) par toutes ses instances : sa géométrie n'est jamais copiée.
+#[derive(Debug)]
+pub struct Group {
+    pub name: String,
+    pub objects: Vec<Object>,
+    bvh: Bvh,
+}
+
+impl Group {
+    pub fn new(name: &str, objects: Vec<Object>) -> Result<Group, String> {
+        if objects.is_empty() {
+            return Err(format!("Group '{}' is empty", name));
+        }
+        let bvh = Bvh::build(&objects);
+        Ok(Group { name: name.to_string(), objects, bvh })
+    }
+}
+
+impl Renderable for Group {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        self.bvh.closest(ray, |index| self.objects[index].intersect(ray))
+    }
+
+    // Matériau du premier objet : chaque objet du groupe garde son propre matériau
+    fn material(&self) -> Material {
+        self.objects[0].material()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounds()
+    }
+}
+
+// Instance d'un groupe : "instance/nom_du_groupe/(x,y,z)" suivie de rotation, scale et translate.
+// L'origine du repère du groupe est placée à la position de l'instance.
+pub fn instance(group: &Arc<Group>, location: Vec3, params: &Params) -> Result<Object, String> {
+    params.check(&TRANSFORM_PARAMS, "instance")?;
+    let transform = Transform::from_params(params)?.unwrap_or_else(Transform::identity);
+    let transform = Transform::translation(location) * transform;
+    Ok(Object::Transformed(Box::new(Transformed::new(Object::Group(Arc::clone(group)), transform))))
+}
@@ -0,0 +1,293 @@
+//Module pour les terrains (champs de hauteur lus dans une image en niveaux de gris)
+
+use std::path::Path;
+use crate::vec3::*;
+use crate::ray::*;
+use crate::bvh::Aabb;
+use crate::material::Material;
+use crate::mesh::intersect_triangle;
+use crate::objects::*;
+
+// Terrain posé sur un rectangle horizontal centré sur sa position : chaque pixel de l'image
+// est un sommet de la grille, à une hauteur proportionnelle à sa luminosité (noir : 0, blanc : height).
+// Chaque cellule de la grille est découpée en deux triangles.
+#[derive(Debug)]
+pub struct Heightfield {
+    pub center: Vec3,
+    pub width: f64,         // Étendue selon x
+    pub depth: f64,         // Étendue selon z
+    pub columns: usize,     // Nombre de sommets selon x (largeur de l'image)
+    pub rows: usize,        // Nombre de sommets selon z (hauteur de l'image, la première ligne au fond, vers -z)
+    pub heights: Vec<f64>,
+    pub normals: Vec<Vec3>, // Normales aux sommets, interpolées sur chaque triangle
+    pub cell_ranges: Vec<(f64, f64)>, // Hauteurs minimale et maximale de chaque cellule
+    pub material: Material,
+    bounds: Aabb,
+}
+
+impl Heightfield {
+    //width par défaut 100, depth par défaut 100, height par défaut 10
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["file", "width", "depth", "height"], &shape.shape_type)?;
+        let file = shape.params.get("file").ok_or("Missing parameter 'file'")?;
+        let width = shape.params.positive("width", 100.0)?;
+        let depth = shape.params.positive("depth", 100.0)?;
+        let height = shape.params.number("height", 10.0)?;
+        if !width.is_finite() || !depth.is_finite() || !height.is_finite() {
+            return Err("Parameters 'width', 'depth' and 'height' must be finite".to_string());
+        }
+
+        let (columns, rows, levels) = load_heightmap(&shape.directory.join(file))?;
+        let heights = levels.into_iter().map(|level| level * height).collect();
+        Heightfield::from_heights(shape.location, width, depth, columns, rows, heights, shape.material)
+    }
+
+    // Terrain à partir de hauteurs déjà lues, ligne par ligne
+    pub fn from_heights(center: Vec3, width: f64, depth: f64, columns: usize, rows: usize, heights: Vec<f64>, material: Material) -> Result<Heightfield, String> {
+        // Il faut au moins une cellule : avec une seule ligne ou colonne, la taille des cellules serait infinie
+        if columns < 2 || rows < 2 {
+            return Err(format!("A heightfield needs at least 2x2 heights, found {}x{}", columns, rows));
+        }
+        if heights.len() != columns * rows {
+            return Err(format!("Expected {} heights for a {}x{} heightfield, found {}", columns * rows, columns, rows, heights.len()));
+        }
+
+        let mut field = Heightfield {
+            center, width, depth, columns, rows, heights,
+            normals: Vec::new(),
+            cell_ranges: Vec::new(),
+            material,
+            bounds: Aabb::empty(),
+        };
+
+        field.normals = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| field.vertex_normal(column, row))
+            .collect();
+        field.cell_ranges = (0..rows - 1)
+            .flat_map(|row| (0..columns - 1).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let corners = [field.height(column, row), field.height(column + 1, row), field.height(column, row + 1), field.height(column + 1, row + 1)];
+                (corners.iter().copied().fold(f64::INFINITY, f64::min), corners.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+            })
+            .collect();
+
+        let (low, high) = field.cell_ranges.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &(min, max)| (low.min(min), high.max(max)));
+        let half = Vec3::new(width / 2.0, 0.0, depth / 2.0);
+        field.bounds = Aabb::new(Vec3::new(-half.x, low, -half.z), Vec3::new(half.x, high, half.z));
+        Ok(field)
+    }
+
+    fn cell_size(&self) -> (f64, f64) {
+        (self.width / (self.columns - 1) as f64, self.depth / (self.rows - 1) as f64)
+    }
+
+    fn height(&self, column: usize, row: usize) -> f64 {
+        self.heights[row * self.columns + column]
+    }
+
+    // Sommet de la grille, dans le repère du terrain
+    fn vertex(&self, column: usize, row: usize) -> Vec3 {
+        let (cell_width, cell_depth) = self.cell_size();
+        Vec3::new(column as f64 * cell_width - self.width / 2.0, self.height(column, row), row as f64 * cell_depth - self.depth / 2.0)
+    }
+
+    // Normale par différences centrées (décentrées sur les bords)
+    fn vertex_normal(&self, column: usize, row: usize) -> Vec3 {
+        let (cell_width, cell_depth) = self.cell_size();
+        let (left, right) = (column.saturating_sub(1), (column + 1).min(self.columns - 1));
+        let (back, front) = (row.saturating_sub(1), (row + 1).min(self.rows - 1));
+        let slope_x = (self.height(right, row) - self.height(left, row)) / ((right - left) as f64 * cell_width);
+        let slope_z = (self.height(column, front) - self.height(column, back)) / ((front - back) as f64 * cell_depth);
+        Vec3::new(-slope_x, 1.0, -slope_z).normalize()
+    }
+
+    // Intersection avec les deux triangles d'une cellule
+    fn intersect_cell(&self, ray: &Ray, column: usize, row: usize) -> Option<Intersection> {
+        let corners = [(column, row), (column, row + 1), (column + 1, row + 1), (column + 1, row)];
+        let mut closest = None;
+        for [a, b, c] in [[corners[0], corners[1], corners[2]], [corners[0], corners[2], corners[3]]] {
+            let Some((t, u, v)) = intersect_triangle(ray, self.vertex(a.0, a.1), self.vertex(b.0, b.1), self.vertex(c.0, c.1)) else {
+                continue;
+            };
+            let normal_of = |(column, row): (usize, usize)| self.normals[row * self.columns + column];
+            let normal = (normal_of(a) * (1.0 - u - v) + normal_of(b) * u + normal_of(c) * v).normalize();
+            closest = closest_hit(closest, Some(Intersection { point: ray.at(t), normal, distance: t, material: self.material }));
+        }
+        closest
+    }
+}
+
+// Lit l'image et renvoie ses dimensions et ses niveaux de gris entre 0 et 1, ligne par ligne
+pub fn load_heightmap(path: &Path) -> Result<(usize, usize, Vec<f64>), String> {
+    let image = image::open(path).map_err(|e| format!("Could not read '{}': {}", path.display(), e))?.to_luma16();
+    let (columns, rows) = (image.width() as usize, image.height() as usize);
+    if columns < 2 || rows < 2 {
+        return Err(format!("Heightmap '{}' must be at least 2x2 pixels", path.display()));
+    }
+    let levels = image.pixels().map(|pixel| pixel.0[0] as f64 / u16::MAX as f64).collect();
+    Ok((columns, rows, levels))
+}
+
+impl Renderable for Heightfield {
+    // Parcours de la grille cellule par cellule le long du rayon (DDA 2D sur le plan xz).
+    // Une cellule n'est testée que si le rayon y passe entre ses hauteurs minimale et maximale.
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let local = Ray::new(ray.origin - self.center, ray.direction);
+        let (o, d) = (local.origin, local.direction);
+
+        let (mut start, mut end) = (0.0_f64, f64::INFINITY);
+        for (o, d, min, max) in [
+            (o.x, d.x, self.bounds.min.x, self.bounds.max.x),
+            (o.y, d.y, self.bounds.min.y, self.bounds.max.y),
+            (o.z, d.z, self.bounds.min.z, self.bounds.max.z),
+        ] {
+            let (t1, t2) = ((min - o) / d, (max - o) / d);
+            start = start.max(t1.min(t2));
+            end = end.min(t1.max(t2));
+        }
+        if start > end {
+            return None;
+        }
+
+        let (cell_width, cell_depth) = self.cell_size();
+        let entry = local.at(start);
+        let cell_of = |position: f64, half: f64, size: f64, count: usize| (((position + half) / size).floor().max(0.0) as usize).min(count - 2);
+        let mut column = cell_of(entry.x, self.width / 2.0, cell_width, self.columns);
+        let mut row = cell_of(entry.z, self.depth / 2.0, cell_depth, self.rows);
+
+        // Distance jusqu'à la prochaine limite de cellule sur un axe, et distance entre deux limites
+        let next_boundary = |index: usize, o: f64, d: f64, half: f64, size: f64| {
+            if d == 0.0 {
+                return (f64::INFINITY, f64::INFINITY);
+            }
+            let boundary = if d > 0.0 { index + 1 } else { index } as f64 * size - half;
+            ((boundary - o) / d, size / d.abs())
+        };
+        let (mut next_x, delta_x) = next_boundary(column, o.x, d.x, self.width / 2.0, cell_width);
+        let (mut next_z, delta_z) = next_boundary(row, o.z, d.z, self.depth / 2.0, cell_depth);
+
+        let mut cell_start = start;
+        loop {
+            let cell_end = next_x.min(next_z).min(end);
+            let (y0, y1) = (o.y + d.y * cell_start, o.y + d.y * cell_end);
+            let (min, max) = self.cell_ranges[row * (self.columns - 1) + column];
+            if y0.max(y1) >= min - 1e-9 && y0.min(y1) <= max + 1e-9 {
+                if let Some(mut hit) = self.intersect_cell(&local, column, row) {
+                    hit.point = ray.at(hit.distance);
+                    return Some(hit);
+                }
+            }
+
+            if cell_end >= end {
+                return None;
+            }
+            if next_x < next_z {
+                if d.x > 0.0 { column += 1 } else if column == 0 { return None } else { column -= 1 }
+                cell_start = next_x;
+                next_x += delta_x;
+                if column > self.columns - 2 {
+                    return None;
+                }
+            } else {
+                if d.z > 0.0 { row += 1 } else if row == 0 { return None } else { row -= 1 }
+                cell_start = next_z;
+                next_z += delta_z;
+                if row > self.rows - 2 {
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(self.center + self.bounds.min, self.center + self.bounds.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Grille de 5x5 sommets sur 40x40 : cellules de 10 unités, hauteurs données par colonne
+    fn terrain(height_of_column: impl Fn(usize) -> f64) -> Heightfield {
+        let heights = (0..5).flat_map(|_| (0..5).map(&height_of_column)).collect();
+        Heightfield::from_heights(Vec3::new(0.0, 0.0, 0.0), 40.0, 40.0, 5, 5, heights, Material::default()).unwrap()
+    }
+
+    // Pente y = (x + 20) / 2 sur tout le terrain
+    fn ramp() -> Heightfield {
+        terrain(|column| column as f64 * 5.0)
+    }
+
+    fn hit(field: &Heightfield, origin: Vec3, direction: Vec3) -> Option<Intersection> {
+        field.intersect(&Ray::new(origin, direction.normalize()))
+    }
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-6, "expected {:?}, found {:?}", b, a);
+    }
+
+    #[test]
+    fn invalid_grids_are_rejected() {
+        let flat = |columns: usize, rows: usize, count: usize| {
+            Heightfield::from_heights(Vec3::new(0.0, 0.0, 0.0), 10.0, 10.0, columns, rows, vec![0.0; count], Material::default())
+        };
+        assert!(flat(0, 0, 0).is_err());
+        assert!(flat(1, 3, 3).is_err());
+        assert!(flat(3, 3, 8).is_err());
+        assert!(flat(2, 2, 4).is_ok());
+    }
+
+    #[test]
+    fn vertical_hit_inside_one_cell() {
+        let intersection = hit(&ramp(), Vec3::new(3.0, 100.0, 3.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((intersection.distance - 88.5).abs() < 1e-6);
+        assert_close(intersection.point, Vec3::new(3.0, 11.5, 3.0));
+        assert_close(intersection.normal, Vec3::new(-1.0, 2.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn hit_after_crossing_cells() {
+        // Le rayon horizontal passe au-dessus de plusieurs cellules avant de toucher la pente à x = -5
+        let field = ramp();
+        let intersection = hit(&field, Vec3::new(30.0, 7.5, 3.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((intersection.distance - 35.0).abs() < 1e-6);
+        assert_close(intersection.point, Vec3::new(-5.0, 7.5, 3.0));
+
+        // En diagonale, il change aussi de ligne
+        let intersection = hit(&field, Vec3::new(30.0, 7.5, 32.0), Vec3::new(-1.0, 0.0, -1.0)).unwrap();
+        assert!((intersection.distance - 35.0 * 2.0_f64.sqrt()).abs() < 1e-6);
+        assert_close(intersection.point, Vec3::new(-5.0, 7.5, -3.0));
+    }
+
+    #[test]
+    fn grazing_rays_miss() {
+        let field = ramp();
+        // Juste au-dessus du point le plus haut
+        assert!(hit(&field, Vec3::new(30.0, 20.1, 3.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+        // Parallèle à la pente, juste au-dessus
+        assert!(hit(&field, Vec3::new(-30.0, -4.9, 3.0), Vec3::new(2.0, 1.0, 0.0)).is_none());
+        // À côté du terrain
+        assert!(hit(&field, Vec3::new(25.0, 100.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn normals_are_interpolated_between_vertices() {
+        // Parabole y = column² : pentes 0.4 et 0.6 aux sommets des colonnes 2 et 3 (différences centrées)
+        let field = terrain(|column| (column * column) as f64);
+        let (left, right) = (Vec3::new(-0.4, 1.0, 0.0).normalize(), Vec3::new(-0.6, 1.0, 0.0).normalize());
+
+        // Au milieu de la cellule, la normale est la moyenne de celles des deux colonnes
+        let intersection = hit(&field, Vec3::new(5.0, 100.0, 3.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert_close(intersection.normal, (left + right).normalize());
+
+        // Près d'une colonne, elle tend vers la normale de son sommet
+        let intersection = hit(&field, Vec3::new(0.001, 100.0, 3.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((intersection.normal - left).length() < 1e-3);
+    }
+}
@@ -0,0 +1,121 @@
+//Module pour les méthodes de rendu (intégrateurs) : calcul de la couleur vue par un rayon de caméra
+
+use std::fmt::Debug;
+use crate::color::*;
+use crate::ray::*;
+use crate::scene::SceneParams;
+use crate::sampler::Sampler;
+use crate::path_tracer::{path_color, cosine_hemisphere};
+use crate::utils::Params;
+
+// Méthode de rendu : couleur (radiance) vue par un rayon de caméra.
+// Les intégrateurs sont partagés entre les threads de rendu ; les nombres aléatoires viennent du générateur du pixel.
+pub trait Integrator: Debug + Send + Sync {
+    fn li(&self, ray: &Ray, scene: &SceneParams, sampler: &mut Sampler) -> Color;
+}
+
+// Lit un intégrateur depuis son nom suivi de paramètres optionnels "clé=valeur" (ex. "ambient_occlusion/distance=20")
+pub fn from_name(value: &str) -> Result<Box<dyn Integrator>, String> {
+    let parts: Vec<&str> = value.split('/').map(|part| part.trim()).collect();
+    let name = parts[0].to_lowercase();
+    let params = Params::parse(&parts[1..])?;
+
+    let integrator: Box<dyn Integrator> = match name.as_str() {
+        "whitted" => {
+            params.check(&[], "whitted integrator")?;
+            Box::new(Whitted)
+        }
+        "path" => {
+            params.check(&[], "path integrator")?;
+            Box::new(PathTracer)
+        }
+        "debug" => {
+            params.check(&["mode"], "debug integrator")?;
+            let mode = match params.get("mode") {
+                None | Some("normal") => DebugMode::Normal,
+                Some("albedo") => DebugMode::Albedo,
+                Some(mode) => return Err(format!("Unknown value '{}' for parameter 'mode', expected normal or albedo", mode)),
+            };
+            Box::new(DebugView { mode })
+        }
+        "ambient_occlusion" => {
+            params.check(&["samples", "distance"], "ambient_occlusion integrator")?;
+            Box::new(AmbientOcclusion { samples: params.count("samples", 16)?, distance: params.positive("distance", 20.0)? })
+        }
+        _ => return Err(format!("Unknown integrator '{}', expected whitted, path, debug or ambient_occlusion", parts[0])),
+    };
+    Ok(integrator)
+}
+
+// Lancer de rayons récursif (Whitted) : ombres, reflets miroir et réfraction, lumière ambiante constante
+#[derive(Debug)]
+pub struct Whitted;
+
+impl Integrator for Whitted {
+    fn li(&self, ray: &Ray, scene: &SceneParams, sampler: &mut Sampler) -> Color {
+        color(ray, scene, sampler)
+    }
+}
+
+// Tracé de chemins : éclairage indirect, à utiliser avec de nombreux échantillons par pixel
+#[derive(Debug)]
+pub struct PathTracer;
+
+impl Integrator for PathTracer {
+    fn li(&self, ray: &Ray, scene: &SceneParams, sampler: &mut Sampler) -> Color {
+        path_color(ray, scene, sampler)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DebugMode {
+    Normal, // Normale de la surface, ses composantes ramenées de [-1, 1] à [0, 1]
+    Albedo, // Couleur diffuse du matériau, sans éclairage
+}
+
+// Vue de contrôle de la géométrie et des matériaux, sans lumière
+#[derive(Debug)]
+pub struct DebugView {
+    pub mode: DebugMode,
+}
+
+impl Integrator for DebugView {
+    fn li(&self, ray: &Ray, scene: &SceneParams, _sampler: &mut Sampler) -> Color {
+        let Some(intersection) = scene.intersect(ray) else {
+            return scene.background_color;
+        };
+        match self.mode {
+            DebugMode::Normal => {
+                let n = intersection.normal;
+                Color::new(n.x * 0.5 + 0.5, n.y * 0.5 + 0.5, n.z * 0.5 + 0.5)
+            }
+            DebugMode::Albedo => intersection.material.diffuse,
+        }
+    }
+}
+
+// Occultation ambiante : part des directions autour du point qui ne rencontrent aucun objet avant `distance`
+#[derive(Debug)]
+pub struct AmbientOcclusion {
+    pub samples: u32,  // Nombre de rayons par point touché
+    pub distance: f64, // Distance au-delà de laquelle un objet n'assombrit plus le point
+}
+
+impl Integrator for AmbientOcclusion {
+    fn li(&self, ray: &Ray, scene: &SceneParams, sampler: &mut Sampler) -> Color {
+        let Some(intersection) = scene.intersect(ray) else {
+            return scene.background_color;
+        };
+        let normal = if ray.direction.dot(intersection.normal) < 0.0 { intersection.normal } else { -intersection.normal };
+        let origin = intersection.point + normal * EPSILON;
+
+        // Directions tirées selon le cosinus : la moyenne est pondérée comme l'éclairage d'un ciel uniforme
+        let visible = (0..self.samples)
+            .filter(|&index| {
+                let direction = cosine_hemisphere(normal, sampler.stratified_2d(index, self.samples));
+                !scene.occluded(&Ray::new(origin, direction), self.distance)
+            })
+            .count();
+        get_color("white").scale(visible as f64 / self.samples as f64)
+    }
+}
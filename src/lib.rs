@@ -6,4 +6,22 @@ pub mod objects;
 pub mod ray;
 pub mod color;
 pub mod scene;
-pub mod camera;
\ No newline at end of file
+pub mod camera;
+pub mod bvh;
+pub mod material;
+pub mod sampler;
+pub mod filter;
+pub mod mesh;
+pub mod obj;
+pub mod transform;
+pub mod group;
+pub mod solver;
+pub mod primitives;
+pub mod csg;
+pub mod sdf;
+pub mod heightfield;
+pub mod light;
+pub mod path_tracer;
+pub mod integrator;
+pub mod emitter;
+pub mod microfacet;
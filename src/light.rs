@@ -0,0 +1,312 @@
+//Module pour les lumières (ponctuelles, directionnelles, projecteurs et lumières surfaciques)
+
+use std::f64::consts::PI;
+use crate::vec3::*;
+use crate::color::*;
+use crate::utils::Params;
+use crate::sampler::Sampler;
+use crate::emitter::Emitter;
+
+// Nombre de rayons d'ombre par point éclairé, pour les lumières surfaciques et les objets émissifs
+pub const DEFAULT_SAMPLES: u32 = 16;
+
+#[derive(Debug)]
+pub enum LightKind {
+    // Lumière émise dans toutes les directions depuis un point
+    Point { position: Vec3, attenuation: Attenuation },
+    // Lumière venant de l'infini (soleil) : tous les rayons sont parallèles
+    Directional { direction: Vec3 }, // Direction de propagation, unitaire
+    // Projecteur : lumière ponctuelle limitée à un cône, avec un bord adouci (pénombre)
+    Spot {
+        position: Vec3,
+        direction: Vec3, // Axe du cône, unitaire
+        cos_outer: f64,  // Cosinus du demi-angle du cône, au-delà duquel rien n'est éclairé
+        cos_inner: f64,  // Cosinus du demi-angle en deçà duquel l'éclairage est complet
+        attenuation: Attenuation,
+    },
+    // Lumières surfaciques : plusieurs rayons d'ombre vers des points répartis sur la surface donnent des ombres douces
+    Sphere { center: Vec3, radius: f64, attenuation: Attenuation },
+    // Rectangle (parallélogramme) dont la position est un coin, éclairant du côté de u × v
+    Rect { corner: Vec3, u: Vec3, v: Vec3, normal: Vec3, attenuation: Attenuation },
+    // Disque éclairant du côté de sa normale
+    Disk { center: Vec3, normal: Vec3, radius: f64, attenuation: Attenuation },
+    // Objet de la scène dont le matériau est émissif
+    Emitter(Box<Emitter>),
+}
+
+// Atténuation avec la distance d : 1 / (constant + linear·d + quadratic·d²)
+#[derive(Debug, Copy, Clone)]
+pub struct Attenuation {
+    pub constant: f64,
+    pub linear: f64,
+    pub quadratic: f64,
+}
+
+impl Attenuation {
+    // Par défaut la lumière ne diminue pas avec la distance, comme avant l'ajout de l'atténuation
+    pub fn none() -> Attenuation {
+        Attenuation { constant: 1.0, linear: 0.0, quadratic: 0.0 }
+    }
+
+    pub fn from_params(params: &Params) -> Result<Attenuation, String> {
+        let Some(value) = params.get("attenuation") else {
+            return Ok(Attenuation::none());
+        };
+        let coefficients = parse_vec3(value).map_err(|e| format!("Invalid value for parameter 'attenuation': {}", e))?;
+        if coefficients.x < 0.0 || coefficients.y < 0.0 || coefficients.z < 0.0 {
+            return Err("Parameter 'attenuation' must not have negative coefficients".to_string());
+        }
+        if coefficients.x + coefficients.y + coefficients.z == 0.0 {
+            return Err("Parameter 'attenuation' needs at least one coefficient greater than 0".to_string());
+        }
+        Ok(Attenuation { constant: coefficients.x, linear: coefficients.y, quadratic: coefficients.z })
+    }
+
+    pub fn factor(&self, distance: f64) -> f64 {
+        1.0 / (self.constant + self.linear * distance + self.quadratic * distance * distance)
+    }
+}
+
+#[derive(Debug)]
+pub struct Light {
+    pub kind: LightKind,
+    pub intensity: f64,
+    pub color: Color,
+    pub samples: u32, // Nombre de rayons d'ombre par point éclairé (1 pour les lumières ponctuelles et directionnelles)
+}
+
+// Éclairage reçu par un point depuis une lumière
+#[derive(Debug, Copy, Clone)]
+pub struct LightSample {
+    pub direction: Vec3, // Direction unitaire du point vers la lumière
+    pub distance: f64,   // Distance jusqu'à la lumière, infinie pour une lumière directionnelle (longueur du rayon d'ombre)
+    pub radiance: Color, // Couleur et intensité reçues, atténuation comprise
+    pub pdf: f64,        // Densité de probabilité de la direction (par angle solide), 0 pour une lumière ponctuelle ou directionnelle
+}
+
+impl Light {
+    // Lumière ponctuelle blanche sans atténuation (ancienne syntaxe light_*)
+    pub fn point(position: Vec3, intensity: f64, color: Color) -> Light {
+        Light { kind: LightKind::Point { position, attenuation: Attenuation::none() }, intensity, color, samples: 1 }
+    }
+
+    // Lumière formée par la surface d'un objet émissif : son éclairage dépend seulement de l'émission du matériau
+    pub fn emitter(emitter: Emitter) -> Light {
+        let samples = emitter.samples;
+        Light { kind: LightKind::Emitter(Box::new(emitter)), intensity: 1.0, color: get_color("white"), samples }
+    }
+
+    // `vector` est la position de la lumière (un coin pour un rectangle), ou sa direction de propagation pour une lumière directionnelle
+    pub fn new(kind: &str, color: Color, vector: Vec3, params: &Params) -> Result<Light, String> {
+        let mut samples = 1;
+        let kind = match kind {
+            "point" => {
+                params.check(&["intensity", "attenuation"], "point light")?;
+                LightKind::Point { position: vector, attenuation: Attenuation::from_params(params)? }
+            }
+            "directional" => {
+                params.check(&["intensity"], "directional light")?;
+                if vector.length() == 0.0 {
+                    return Err("The direction of a directional light must not be (0,0,0)".to_string());
+                }
+                LightKind::Directional { direction: vector.normalize() }
+            }
+            "spot" => {
+                params.check(&["intensity", "attenuation", "direction", "angle", "penumbra"], "spot light")?;
+                let direction = params.vec3("direction", Vec3::new(0.0, -1.0, 0.0))?;
+                if direction.length() == 0.0 {
+                    return Err("Parameter 'direction' must not be (0,0,0)".to_string());
+                }
+                let angle = params.positive("angle", 30.0)?;
+                if angle > 90.0 {
+                    return Err(format!("Parameter 'angle' must not be greater than 90 degrees, found {}", angle));
+                }
+                let penumbra = params.number("penumbra", 5.0)?;
+                if !(0.0..=angle).contains(&penumbra) {
+                    return Err(format!("Parameter 'penumbra' must be between 0 and the angle ({}), found {}", angle, penumbra));
+                }
+                LightKind::Spot {
+                    position: vector,
+                    direction: direction.normalize(),
+                    cos_outer: angle.to_radians().cos(),
+                    cos_inner: (angle - penumbra).to_radians().cos(),
+                    attenuation: Attenuation::from_params(params)?,
+                }
+            }
+            "sphere" => {
+                params.check(&["intensity", "attenuation", "samples", "radius"], "sphere light")?;
+                samples = params.count("samples", DEFAULT_SAMPLES)?;
+                LightKind::Sphere { center: vector, radius: params.positive("radius", 5.0)?, attenuation: Attenuation::from_params(params)? }
+            }
+            "rect" => {
+                params.check(&["intensity", "attenuation", "samples", "u", "v"], "rect light")?;
+                samples = params.count("samples", DEFAULT_SAMPLES)?;
+                let u = params.vec3("u", Vec3::new(20.0, 0.0, 0.0))?;
+                let v = params.vec3("v", Vec3::new(0.0, 0.0, 20.0))?;
+                let normal = u.cross(v);
+                if normal.length() < 1e-12 {
+                    return Err("Parameters 'u' and 'v' must not be null or parallel".to_string());
+                }
+                LightKind::Rect { corner: vector, u, v, normal: normal.normalize(), attenuation: Attenuation::from_params(params)? }
+            }
+            "disk" => {
+                params.check(&["intensity", "attenuation", "samples", "radius", "normal"], "disk light")?;
+                samples = params.count("samples", DEFAULT_SAMPLES)?;
+                let normal = params.vec3("normal", Vec3::new(0.0, -1.0, 0.0))?;
+                if normal.length() < 1e-12 {
+                    return Err("Parameter 'normal' must not be a null vector".to_string());
+                }
+                LightKind::Disk { center: vector, normal: normal.normalize(), radius: params.positive("radius", 5.0)?, attenuation: Attenuation::from_params(params)? }
+            }
+            _ => return Err(format!("Unknown light type '{}', expected point, directional, spot, sphere, rect or disk", kind)),
+        };
+
+        let intensity = params.number("intensity", 1.0)?;
+        if intensity < 0.0 {
+            return Err(format!("Parameter 'intensity' must not be negative, found {}", intensity));
+        }
+        Ok(Light { kind, intensity, color, samples })
+    }
+
+    // Lumière ponctuelle, directionnelle ou projecteur : aucun rayon ne peut la toucher, seul un rayon d'ombre l'atteint
+    pub fn is_delta(&self) -> bool {
+        matches!(self.kind, LightKind::Point { .. } | LightKind::Directional { .. } | LightKind::Spot { .. })
+    }
+
+    // Éclairage reçu au point donné par l'échantillon `index` sur `samples` (None si le point est hors du cône
+    // d'un projecteur, ou derrière une lumière surfacique). La somme des échantillons donne l'éclairage total.
+    pub fn illuminate(&self, point: Vec3, index: u32, sampler: &mut Sampler) -> Option<LightSample> {
+        let random = if self.is_delta() { (0.5, 0.5) } else { sampler.stratified_2d(index, self.samples) };
+        let sample = self.sample(point, random)?;
+        Some(LightSample { radiance: sample.radiance * (1.0 / self.samples as f64), ..sample })
+    }
+
+    // Éclairage reçu au point donné depuis un point de la lumière choisi par les nombres aléatoires `random` (dans [0, 1[²)
+    pub fn sample(&self, point: Vec3, random: (f64, f64)) -> Option<LightSample> {
+        let (s1, s2) = random;
+        let (direction, distance, factor, pdf) = match &self.kind {
+            LightKind::Point { position, attenuation } => {
+                let distance = (*position - point).length();
+                ((*position - point).normalize(), distance, attenuation.factor(distance), 0.0)
+            }
+            LightKind::Directional { direction } => (-*direction, f64::INFINITY, 1.0, 0.0),
+            LightKind::Spot { position, direction: axis, cos_outer, cos_inner, attenuation } => {
+                let distance = (*position - point).length();
+                let direction = (*position - point).normalize();
+                let cone = smoothstep(*cos_outer, *cos_inner, (-direction).dot(*axis));
+                if cone <= 0.0 {
+                    return None;
+                }
+                (direction, distance, cone * attenuation.factor(distance), 0.0)
+            }
+            LightKind::Sphere { center, radius, attenuation } => {
+                let to_center = *center - point;
+                let center_distance = to_center.length();
+                if center_distance <= *radius {
+                    // Point à l'intérieur de la sphère : éclairé depuis le centre, comme par une lumière ponctuelle
+                    (to_center.normalize(), center_distance, attenuation.factor(center_distance), 0.0)
+                } else {
+                    // Direction tirée uniformément dans le cône sous lequel la sphère est vue depuis le point
+                    let axis = to_center / center_distance;
+                    let (first, second) = axis.orthonormal_basis();
+                    let cos_max = (1.0 - radius * radius / (center_distance * center_distance)).sqrt();
+                    let cos_theta = 1.0 - s1 * (1.0 - cos_max);
+                    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+                    let phi = 2.0 * PI * s2;
+                    let direction = (first * (phi.cos() * sin_theta) + second * (phi.sin() * sin_theta) + axis * cos_theta).normalize();
+                    // Distance jusqu'à la face de la sphère tournée vers le point
+                    let distance = center_distance * cos_theta - (radius * radius - center_distance * center_distance * sin_theta * sin_theta).max(0.0).sqrt();
+                    (direction, distance, attenuation.factor(distance), 1.0 / (2.0 * PI * (1.0 - cos_max)))
+                }
+            }
+            LightKind::Rect { corner, u, v, normal, attenuation } => {
+                planar_sample(point, *corner + *u * s1 + *v * s2, *normal, u.cross(*v).length(), attenuation)?
+            }
+            LightKind::Disk { center, normal, radius, attenuation } => {
+                let (first, second) = normal.orthonormal_basis();
+                let (r, phi) = (radius * s1.sqrt(), 2.0 * PI * s2);
+                planar_sample(point, *center + first * (r * phi.cos()) + second * (r * phi.sin()), *normal, PI * radius * radius, attenuation)?
+            }
+            LightKind::Emitter(emitter) => return emitter.sample(point, random),
+        };
+
+        Some(LightSample { direction, distance, radiance: self.color * (self.intensity * factor), pdf })
+    }
+
+    // Échantillon qu'aurait donné `sample` pour la direction (unitaire) donnée, si un rayon partant du point
+    // dans cette direction touche la lumière (seulement pour les lumières surfaciques)
+    pub fn hit(&self, point: Vec3, direction: Vec3) -> Option<LightSample> {
+        let (distance, factor, pdf) = match &self.kind {
+            // Un objet émissif est touché comme les autres objets de la scène
+            LightKind::Point { .. } | LightKind::Directional { .. } | LightKind::Spot { .. } | LightKind::Emitter(_) => return None,
+            LightKind::Sphere { center, radius, attenuation } => {
+                let oc = point - *center;
+                let center_distance = oc.length();
+                if center_distance <= *radius {
+                    return None;
+                }
+                let b = oc.dot(direction);
+                let discriminant = b * b - (oc.dot(oc) - radius * radius);
+                if discriminant < 0.0 || -b - discriminant.sqrt() <= 0.0 {
+                    return None;
+                }
+                let distance = -b - discriminant.sqrt();
+                let cos_max = (1.0 - radius * radius / (center_distance * center_distance)).sqrt();
+                (distance, attenuation.factor(distance), 1.0 / (2.0 * PI * (1.0 - cos_max)))
+            }
+            LightKind::Rect { corner, u, v, normal, attenuation } => {
+                let on_light = plane_hit(point, direction, *corner, *normal)?;
+                // Coordonnées du point dans le parallélogramme, entre 0 et 1 sur chaque arête
+                let n = u.cross(*v);
+                let w = n / n.dot(n);
+                let local = on_light - *corner;
+                if !(0.0..=1.0).contains(&w.dot(local.cross(*v))) || !(0.0..=1.0).contains(&w.dot(u.cross(local))) {
+                    return None;
+                }
+                let (_, distance, factor, pdf) = planar_sample(point, on_light, *normal, n.length(), attenuation)?;
+                (distance, factor, pdf)
+            }
+            LightKind::Disk { center, normal, radius, attenuation } => {
+                let on_light = plane_hit(point, direction, *center, *normal)?;
+                if (on_light - *center).length() > *radius {
+                    return None;
+                }
+                let (_, distance, factor, pdf) = planar_sample(point, on_light, *normal, PI * radius * radius, attenuation)?;
+                (distance, factor, pdf)
+            }
+        };
+
+        Some(LightSample { direction, distance, radiance: self.color * (self.intensity * factor), pdf })
+    }
+}
+
+// Échantillon d'une lumière plane : l'éclairage diminue avec l'inclinaison de la surface émettrice vue depuis le point.
+// La densité d'un point tiré uniformément sur la surface est convertie en densité par angle solide.
+fn planar_sample(point: Vec3, on_light: Vec3, normal: Vec3, area: f64, attenuation: &Attenuation) -> Option<(Vec3, f64, f64, f64)> {
+    let distance = (on_light - point).length();
+    let direction = (on_light - point).normalize();
+    let cos_light = -direction.dot(normal);
+    if cos_light <= 0.0 {
+        return None;
+    }
+    Some((direction, distance, cos_light * attenuation.factor(distance), distance * distance / (area * cos_light)))
+}
+
+// Point où un rayon touche la face éclairante d'une lumière plane
+fn plane_hit(point: Vec3, direction: Vec3, on_plane: Vec3, normal: Vec3) -> Option<Vec3> {
+    let denominator = direction.dot(normal);
+    if denominator >= 0.0 {
+        return None;
+    }
+    let t = (on_plane - point).dot(normal) / denominator;
+    (t > 0.0).then(|| point + direction * t)
+}
+
+// Transition douce de 0 (x <= edge0) à 1 (x >= edge1)
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    if edge1 <= edge0 {
+        return if x >= edge0 { 1.0 } else { 0.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
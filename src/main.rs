@@ -1,25 +1,77 @@
 use std::env;
 use std::process;
+use std::thread;
 use rt::utils::*;
+use rt::integrator;
+
+const USAGE: &str = "Usage: cargo run configs/audit00.txt output.ppm [--threads N] [--integrator NAME] OR cargo run configs/audit00.txt output.png [--threads N] [--integrator NAME]";
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 3 {
-        eprintln!("Usage: cargo run configs/audit00.txt output.ppm OR cargo run configs/audit00.txt output.png");
+    // Par défaut, un thread de rendu par cœur disponible
+    let mut threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let mut files = Vec::new();
+    // Intégrateur choisi en ligne de commande, prioritaire sur la clé integrator du fichier de configuration
+    let mut integrator = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--threads" {
+            threads = match args.get(i + 1).map(|value| value.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => n,
+                _ => {
+                    eprintln!("Error: --threads expects a number greater than 0.");
+                    process::exit(1);
+                }
+            };
+            i += 2;
+        } else if args[i] == "--integrator" {
+            integrator = match args.get(i + 1).map(|value| integrator::from_name(value)) {
+                Some(Ok(chosen)) => Some(chosen),
+                Some(Err(e)) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+                None => {
+                    eprintln!("Error: --integrator expects a name (whitted, path, debug or ambient_occlusion).");
+                    process::exit(1);
+                }
+            };
+            i += 2;
+        } else {
+            files.push(&args[i]);
+            i += 1;
+        }
+    }
+
+    if files.len() != 2 {
+        eprintln!("{}", USAGE);
         process::exit(1);
     }
 
-    let config_file = &args[1];
-    let output_file = &args[2];
+    let config_file = files[0];
+    let output_file = files[1];
 
     // Vérifier que `output_file` ne contient pas de chiffre
-    if output_file.chars().any(|c| c.is_digit(10)) {
+    if output_file.chars().any(|c| c.is_ascii_digit()) {
         eprintln!("Error: The output file name should not contain any digits.");
         process::exit(1);
     }
 
-    let scene_params = parse_config_file(&config_file);
-    
-    scene_params.render(&output_file);
-}
\ No newline at end of file
+    let mut scene_params = match parse_config_file(config_file) {
+        Ok(scene_params) => scene_params,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("Error: {}", error);
+            }
+            process::exit(1);
+        }
+    };
+
+    if let Some(integrator) = integrator {
+        scene_params.integrator = integrator;
+    }
+
+    scene_params.render(output_file, threads);
+}
@@ -0,0 +1,84 @@
+//Module pour les matériaux des objets (couleur diffuse, reflets, transparence)
+
+use crate::color::*;
+use crate::microfacet::Microfacet;
+use crate::light::DEFAULT_SAMPLES;
+use crate::utils::Params;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Material {
+    pub diffuse: Color,     // Albédo (couleur diffuse)
+    pub specular: Color,    // Couleur des reflets spéculaires
+    pub shininess: f64,     // Exposant spéculaire : plus il est grand, plus le reflet est petit et net
+    pub reflectivity: f64,  // Part de la couleur réfléchie comme un miroir (0 à 1)
+    pub transparency: f64,  // Part de la lumière transmise à travers l'objet (0 à 1)
+    pub ior: f64,           // Indice de réfraction
+    pub emission: Color,    // Lumière émise par la surface (noir : l'objet n'éclaire pas)
+    pub emission_samples: u32, // Rayons d'ombre vers l'objet émissif par point éclairé (rendu whitted)
+    pub microfacet: Option<Microfacet>, // Modèle métal / rugosité, qui remplace la couleur diffuse et le reflet de Blinn-Phong
+}
+
+impl Default for Material {
+    // Matériau blanc mat, sans reflet
+    fn default() -> Material {
+        Material {
+            diffuse: get_color("white"),
+            specular: get_color("black"),
+            shininess: 32.0,
+            reflectivity: 0.0,
+            transparency: 0.0,
+            ior: 1.5,
+            emission: get_color("black"),
+            emission_samples: DEFAULT_SAMPLES,
+            microfacet: None,
+        }
+    }
+}
+
+impl Material {
+    // Matériau utilisé quand une forme n'indique qu'une couleur : les scènes existantes gardent le reflet de 0.5
+    pub fn from_color(color: Color) -> Material {
+        Material { diffuse: color, reflectivity: 0.5, ..Material::default() }
+    }
+
+    // Lit un matériau depuis ses paramètres "clé=valeur" ; les paramètres absents gardent les valeurs par défaut
+    pub fn from_params(params: &Params) -> Result<Material, String> {
+        params.check(&["diffuse", "specular", "shininess", "reflectivity", "transparency", "ior", "emission", "emission_samples", "base_color", "metallic", "roughness"], "material")?;
+        let default = Material::default();
+
+        let mut material = Material {
+            diffuse: params.color("diffuse", default.diffuse)?,
+            specular: params.color("specular", default.specular)?,
+            shininess: params.number("shininess", default.shininess)?,
+            reflectivity: params.ratio("reflectivity", default.reflectivity)?,
+            transparency: params.ratio("transparency", default.transparency)?,
+            ior: params.positive("ior", default.ior)?,
+            emission: params.color("emission", default.emission)?,
+            emission_samples: params.count("emission_samples", default.emission_samples)?,
+            microfacet: None,
+        };
+
+        // Les paramètres base_color, metallic et roughness décrivent un matériau à microfacettes (convention glTF)
+        if ["base_color", "metallic", "roughness"].iter().any(|key| params.get(key).is_some()) {
+            if let Some(key) = ["diffuse", "specular", "shininess", "reflectivity"].into_iter().find(|key| params.get(key).is_some()) {
+                return Err(format!("Parameter '{}' cannot be used with base_color, metallic or roughness", key));
+            }
+            let microfacet = Microfacet {
+                base_color: params.color("base_color", get_color("white"))?,
+                metallic: params.ratio("metallic", 0.0)?,
+                roughness: params.ratio("roughness", 0.5)?,
+            };
+            material.diffuse = microfacet.base_color;
+            material.microfacet = Some(microfacet);
+        }
+
+        if material.shininess < 0.0 {
+            return Err(format!("Parameter 'shininess' must not be negative, found {}", material.shininess));
+        }
+        if material.reflectivity + material.transparency > 1.0 {
+            return Err("reflectivity + transparency must not be greater than 1".to_string());
+        }
+
+        Ok(material)
+    }
+}
@@ -0,0 +1,216 @@
+//Module pour les triangles et les maillages de triangles
+
+use std::collections::HashMap;
+use std::ops::Range;
+use crate::vec3::*;
+use crate::ray::*;
+use crate::bvh::{Aabb, Bvh};
+use crate::material::Material;
+use crate::objects::*;
+
+// Intersection rayon-triangle de Möller-Trumbore.
+// Renvoie la distance t et les coordonnées barycentriques (u, v) du point touché (poids des sommets b et c).
+pub fn intersect_triangle(ray: &Ray, a: Vec3, b: Vec3, c: Vec3) -> Option<(f64, f64, f64)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+
+    // Rayon parallèle au plan du triangle
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let s = ray.origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inv_det;
+    if t > 1e-9 {
+        Some((t, u, v))
+    } else {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct Triangle {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub c: Vec3,
+    pub normal: Vec3, // Normale géométrique, orientée selon l'ordre des sommets (sens trigonométrique)
+    pub material: Material,
+}
+
+impl Triangle {
+    // Le premier sommet est la position de la forme, les deux autres sont donnés par les paramètres b et c
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["b", "c"], &shape.shape_type)?;
+        let a = shape.location;
+        let b = shape.params.vec3("b", a + Vec3::new(10.0, 0.0, 0.0))?;
+        let c = shape.params.vec3("c", a + Vec3::new(0.0, 10.0, 0.0))?;
+
+        let normal = (b - a).cross(c - a);
+        if normal.length() < 1e-12 {
+            return Err("The three vertices of the triangle are aligned".to_string());
+        }
+
+        Ok(Triangle { a, b, c, normal: normal.normalize(), material: shape.material })
+    }
+}
+
+impl Renderable for Triangle {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let (t, _, _) = intersect_triangle(ray, self.a, self.b, self.c)?;
+        Some(Intersection { point: ray.at(t), normal: self.normal, distance: t, material: self.material })
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(self.a.min(&self.b).min(&self.c), self.a.max(&self.b).max(&self.c))
+    }
+}
+
+// Maillage : sommets partagés entre les triangles, qui ne stockent que les indices de leurs sommets.
+// Les normales par sommet, si elles sont fournies, sont interpolées pour un ombrage lisse.
+#[derive(Debug)]
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    pub normals: Option<Vec<Vec3>>,
+    pub texcoords: Option<Vec<[f64; 2]>>,   // Coordonnées de texture par sommet, si le modèle en a
+    pub indices: Vec<[usize; 3]>,
+    pub material: Material,                 // Matériau des faces sans matériau propre
+    pub materials: Vec<Material>,           // Matériaux référencés par face_materials
+    pub face_materials: Vec<Option<usize>>, // Matériau de chaque face (vide si toutes utilisent `material`)
+    pub groups: Vec<MeshGroup>,
+    bvh: Bvh,
+}
+
+// Groupe nommé de faces consécutives d'un maillage (groupes et objets d'un fichier OBJ)
+#[derive(Debug, Clone)]
+pub struct MeshGroup {
+    pub name: String,
+    pub faces: Range<usize>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vec3>, normals: Option<Vec<Vec3>>, indices: Vec<[usize; 3]>, material: Material) -> Result<Mesh, String> {
+        if indices.is_empty() {
+            return Err("A mesh needs at least one triangle".to_string());
+        }
+        if let Some(index) = indices.iter().flatten().find(|&&index| index >= vertices.len()) {
+            return Err(format!("Vertex index {} is out of range ({} vertices)", index, vertices.len()));
+        }
+        if let Some(normals) = &normals {
+            if normals.len() != vertices.len() {
+                return Err(format!("Expected one normal per vertex ({}), found {}", vertices.len(), normals.len()));
+            }
+        }
+
+        let boxes: Vec<Aabb> = indices.iter()
+            .map(|&[a, b, c]| Aabb::new(vertices[a].min(&vertices[b]).min(&vertices[c]), vertices[a].max(&vertices[b]).max(&vertices[c])))
+            .collect();
+        let bvh = Bvh::from_boxes(&boxes);
+
+        Ok(Mesh { vertices, normals, texcoords: None, indices, material, materials: Vec::new(), face_materials: Vec::new(), groups: Vec::new(), bvh })
+    }
+
+    // Donne à chaque face un matériau de `materials` (None : matériau par défaut du maillage)
+    pub fn set_face_materials(&mut self, materials: Vec<Material>, face_materials: Vec<Option<usize>>) -> Result<(), String> {
+        if face_materials.len() != self.indices.len() {
+            return Err(format!("Expected one material per face ({}), found {}", self.indices.len(), face_materials.len()));
+        }
+        if let Some(index) = face_materials.iter().flatten().find(|&&index| index >= materials.len()) {
+            return Err(format!("Material index {} is out of range ({} materials)", index, materials.len()));
+        }
+        self.materials = materials;
+        self.face_materials = face_materials;
+        Ok(())
+    }
+
+    pub fn face_material(&self, face: usize) -> Material {
+        match self.face_materials.get(face) {
+            Some(Some(index)) => self.materials[*index],
+            _ => self.material,
+        }
+    }
+
+    fn intersect_face(&self, ray: &Ray, face: usize) -> Option<Intersection> {
+        let [ia, ib, ic] = self.indices[face];
+        let (a, b, c) = (self.vertices[ia], self.vertices[ib], self.vertices[ic]);
+        let (t, u, v) = intersect_triangle(ray, a, b, c)?;
+
+        let geometric_normal = (b - a).cross(c - a).normalize();
+        let normal = match &self.normals {
+            Some(normals) => {
+                let smooth = (normals[ia] * (1.0 - u - v) + normals[ib] * u + normals[ic] * v).normalize();
+                // Une normale interpolée nulle (normales opposées) retombe sur la normale géométrique
+                if smooth.length() > 0.0 { smooth } else { geometric_normal }
+            }
+            None => geometric_normal,
+        };
+
+        Some(Intersection { point: ray.at(t), normal, distance: t, material: self.face_material(face) })
+    }
+
+    // Vrai si chaque arête est partagée par exactement deux faces : le maillage délimite alors un volume.
+    // Les sommets sont comparés par leur position, car un fichier OBJ duplique ceux qui ont plusieurs normales.
+    pub fn is_closed(&self) -> bool {
+        // + 0.0 confond 0 et -0
+        let key = |v: Vec3| [(v.x + 0.0).to_bits(), (v.y + 0.0).to_bits(), (v.z + 0.0).to_bits()];
+        let mut edges: HashMap<([u64; 3], [u64; 3]), usize> = HashMap::new();
+        for &[a, b, c] in &self.indices {
+            for (first, second) in [(a, b), (b, c), (c, a)] {
+                let (first, second) = (key(self.vertices[first]), key(self.vertices[second]));
+                *edges.entry((first.min(second), first.max(second))).or_default() += 1;
+            }
+        }
+        edges.values().all(|&count| count == 2)
+    }
+
+    // Toutes les traversées des faces par la droite du rayon, y compris derrière l'origine, triées
+    pub fn crossings(&self, ray: &Ray) -> Vec<Intersection> {
+        // Le rayon est reculé hors de la boîte englobante pour que le BVH donne aussi les faces derrière l'origine
+        let bounds = self.bvh.bounds();
+        let back = ((ray.origin - bounds.centroid()).length() + (bounds.max - bounds.min).length()) / ray.direction.length();
+        let shifted = Ray::new(ray.origin - ray.direction * back, ray.direction);
+
+        let mut hits = Vec::new();
+        self.bvh.visit(&shifted, |face| {
+            if let Some(hit) = self.intersect_face(&shifted, face) {
+                let distance = hit.distance - back;
+                hits.push(Intersection { point: ray.at(distance), distance, ..hit });
+            }
+        });
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        // Un rayon qui passe par une arête ou un sommet traverse plusieurs faces au même point
+        hits.dedup_by(|next, previous| (next.distance - previous.distance).abs() < 1e-9 * (1.0 + previous.distance.abs()));
+        hits
+    }
+}
+
+impl Renderable for Mesh {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        self.bvh.closest(ray, |face| self.intersect_face(ray, face))
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounds()
+    }
+}
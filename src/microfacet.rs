@@ -0,0 +1,148 @@
+//Module pour les matériaux à microfacettes (modèle métal / rugosité de glTF)
+
+use std::f64::consts::PI;
+use crate::color::*;
+use crate::vec3::Vec3;
+use crate::path_tracer::cosine_hemisphere;
+
+// Rugosité minimale de la distribution : une surface parfaitement lisse ne peut pas être échantillonnée par les lumières
+const MIN_ALPHA: f64 = 1e-3;
+// Réflectance des diélectriques sous incidence normale (matériaux non métalliques)
+const DIELECTRIC_F0: f64 = 0.04;
+
+// Surface faite de microfacettes miroirs orientées selon la distribution GGX (Trowbridge-Reitz),
+// avec l'ombrage mutuel de Smith et le coefficient de Fresnel de Schlick.
+// Un métal n'a que le reflet, teinté par sa couleur ; un diélectrique a un reflet blanc et une couleur diffuse.
+#[derive(Debug, Copy, Clone)]
+pub struct Microfacet {
+    pub base_color: Color, // Couleur diffuse d'un diélectrique, ou couleur du reflet d'un métal
+    pub metallic: f64,     // 0 : diélectrique, 1 : métal
+    pub roughness: f64,    // 0 : surface polie, 1 : surface très rugueuse
+}
+
+impl Microfacet {
+    // Paramètre de la distribution GGX, comme dans glTF : le carré de la rugosité
+    fn alpha(&self) -> f64 {
+        (self.roughness * self.roughness).max(MIN_ALPHA)
+    }
+
+    // Couleur du reflet sous incidence normale
+    fn f0(&self) -> Color {
+        Color::new(DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0).scale(1.0 - self.metallic) + self.base_color.scale(self.metallic)
+    }
+
+    // Approximation de Schlick, pour l'angle entre la direction et la microfacette
+    pub fn fresnel(&self, cos: f64) -> Color {
+        let f0 = self.f0();
+        let weight = (1.0 - cos.clamp(0.0, 1.0)).powi(5);
+        Color::new(f0.r + (1.0 - f0.r) * weight, f0.g + (1.0 - f0.g) * weight, f0.b + (1.0 - f0.b) * weight)
+    }
+
+    // Densité des normales des microfacettes (GGX), pour le cosinus entre la normale et la microfacette
+    pub fn distribution(&self, cos_h: f64) -> f64 {
+        if cos_h <= 0.0 {
+            return 0.0;
+        }
+        let a2 = self.alpha() * self.alpha();
+        let denominator = cos_h * cos_h * (a2 - 1.0) + 1.0;
+        a2 / (PI * denominator * denominator)
+    }
+
+    // Fonction auxiliaire Λ de Smith pour GGX
+    fn lambda(&self, cos: f64) -> f64 {
+        let cos2 = cos * cos;
+        let tan2 = (1.0 - cos2).max(0.0) / cos2;
+        ((1.0 + self.alpha() * self.alpha() * tan2).sqrt() - 1.0) / 2.0
+    }
+
+    // Part des microfacettes visibles depuis une direction (masquage de Smith)
+    pub fn masking(&self, cos: f64) -> f64 {
+        1.0 / (1.0 + self.lambda(cos))
+    }
+
+    // Part des microfacettes visibles à la fois de l'observateur et de la lumière (Smith, forme corrélée en hauteur)
+    pub fn masking_shadowing(&self, cos_view: f64, cos_light: f64) -> f64 {
+        1.0 / (1.0 + self.lambda(cos_view) + self.lambda(cos_light))
+    }
+
+    // Couleur diffuse, absente des métaux
+    fn diffuse(&self) -> Color {
+        self.base_color.scale(1.0 - self.metallic)
+    }
+
+    // Réponse à une lumière venant de `light` pour un observateur dans la direction `view` (vecteurs unitaires
+    // partant de la surface) : BRDF × π × cosinus, comme l'albédo × cosinus d'une surface mate
+    pub fn eval(&self, normal: Vec3, view: Vec3, light: Vec3) -> Color {
+        let (cos_view, cos_light) = (normal.dot(view), normal.dot(light));
+        if cos_view <= 0.0 || cos_light <= 0.0 {
+            return get_color("black");
+        }
+        let half_vector = (view + light).normalize();
+        let fresnel = self.fresnel(view.dot(half_vector));
+        let specular = self.distribution(normal.dot(half_vector)) * self.masking_shadowing(cos_view, cos_light) / (4.0 * cos_view * cos_light);
+
+        // La lumière qui n'est pas réfléchie par les microfacettes est diffusée (Lambert)
+        let transmitted = Color::new(1.0 - fresnel.r, 1.0 - fresnel.g, 1.0 - fresnel.b);
+        (fresnel * (specular * PI) + transmitted * self.diffuse()) * cos_light
+    }
+
+    // Probabilité de tirer un rebond sur le reflet plutôt que diffus, selon leurs poids vus depuis `view`
+    fn specular_probability(&self, cos_view: f64) -> f64 {
+        let average = |color: Color| (color.r + color.g + color.b) / 3.0;
+        let specular = average(self.fresnel(cos_view));
+        let diffuse = average(self.diffuse()) * (1.0 - specular);
+        if specular + diffuse == 0.0 { 1.0 } else { specular / (specular + diffuse) }
+    }
+
+    // Direction de rebond : soit réfléchie sur une microfacette tirée parmi celles visibles depuis `view`
+    // (échantillonnage des normales visibles de Heitz), soit diffuse, tirée selon le cosinus
+    pub fn sample(&self, normal: Vec3, view: Vec3, choice: f64, random: (f64, f64)) -> Option<Vec3> {
+        let cos_view = normal.dot(view);
+        if cos_view <= 0.0 {
+            return None;
+        }
+        if choice >= self.specular_probability(cos_view) {
+            return Some(cosine_hemisphere(normal, random));
+        }
+
+        // Direction d'observation dans le repère de la surface, étirée pour ramener la distribution à alpha = 1
+        let alpha = self.alpha();
+        let (first, second) = normal.orthonormal_basis();
+        let local = Vec3::new(view.dot(first) * alpha, view.dot(second) * alpha, cos_view).normalize();
+
+        // Point tiré sur le demi-disque vu depuis cette direction, puis projeté sur l'hémisphère
+        let length = (local.x * local.x + local.y * local.y).sqrt();
+        let t1 = if length > 0.0 { Vec3::new(-local.y / length, local.x / length, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let t2 = local.cross(t1);
+        let (r, phi) = (random.0.sqrt(), 2.0 * PI * random.1);
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + local.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * r * phi.sin();
+        let h = t1 * p1 + t2 * p2 + local * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+
+        let half_vector = (first * (h.x * alpha) + second * (h.y * alpha) + normal * h.z.max(0.0)).normalize();
+        let light = half_vector * (2.0 * view.dot(half_vector)) - view;
+        if normal.dot(light) <= 0.0 {
+            return None;
+        }
+        Some(light)
+    }
+
+    // Densité (par angle solide) avec laquelle `sample` choisit la direction `light`
+    pub fn pdf(&self, normal: Vec3, view: Vec3, light: Vec3) -> f64 {
+        let (cos_view, cos_light) = (normal.dot(view), normal.dot(light));
+        if cos_view <= 0.0 || cos_light <= 0.0 {
+            return 0.0;
+        }
+        let half_vector = (view + light).normalize();
+        let specular_pdf = self.masking(cos_view) * self.distribution(normal.dot(half_vector)) / (4.0 * cos_view);
+        let probability = self.specular_probability(cos_view);
+        probability * specular_pdf + (1.0 - probability) * cos_light / PI
+    }
+
+    // Part de la scène reflétée dans la direction miroir, pour le rendu whitted qui ne trace pas de reflets flous :
+    // coefficient de Fresnel, atténué quand la rugosité étale le reflet
+    pub fn mirror_reflectance(&self, cos_view: f64) -> Color {
+        self.fresnel(cos_view).scale((1.0 - self.roughness).powi(2))
+    }
+}
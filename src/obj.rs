@@ -0,0 +1,271 @@
+//Module pour l'import de modèles au format Wavefront OBJ (et de leurs matériaux MTL)
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::vec3::*;
+use crate::color::*;
+use crate::material::Material;
+use crate::mesh::*;
+use crate::objects::Shape;
+use crate::transform::Transform;
+
+// Contenu d'un fichier OBJ, avec ses indices déjà résolus (à partir de 0)
+#[derive(Debug, Default)]
+pub struct ObjModel {
+    pub positions: Vec<Vec3>,
+    pub texcoords: Vec<[f64; 2]>,
+    pub normals: Vec<Vec3>,
+    pub faces: Vec<ObjFace>,                   // Triangles (les polygones sont découpés en éventail)
+    pub groups: Vec<MeshGroup>,                // Groupes (g) et objets (o), par plages de faces
+    pub materials: Vec<(String, Material)>,    // Matériaux chargés depuis les fichiers MTL
+}
+
+// Triangle d'un fichier OBJ : pour chaque coin, indices de position, de coordonnée de texture et de normale
+#[derive(Debug, Clone)]
+pub struct ObjFace {
+    pub corners: [(usize, Option<usize>, Option<usize>); 3],
+    pub material: Option<usize>, // Indice dans ObjModel::materials (usemtl)
+}
+
+// Crée un maillage à partir d'une forme "obj/matériau/(x,y,z)/file=modele.obj/..."
+// Le matériau de la forme est utilisé pour les faces sans matériau MTL.
+pub fn load_obj_mesh(shape: Shape, transform: Transform) -> Result<Mesh, String> {
+    shape.params.check(&["file", "group"], &shape.shape_type)?;
+    let file = shape.params.get("file").ok_or("Missing parameter 'file'")?;
+    let path = shape.directory.join(file);
+
+    // Le modèle est placé à la position de la forme après sa transformation
+    let transform = Transform::translation(shape.location) * transform;
+    let model = load_obj(&path)?;
+    model.to_mesh(shape.params.get("group"), shape.material, |p| transform.point(p), |n| transform.normal(n))
+}
+
+pub fn load_obj(path: &Path) -> Result<ObjModel, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path.display(), e))?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    parse_obj(&content, directory).map_err(|(line, reason)| format!("{}:{}: {}", path.display(), line, reason))
+}
+
+// Lit le contenu d'un fichier OBJ ; les erreurs indiquent le numéro de ligne
+pub fn parse_obj(content: &str, directory: &Path) -> Result<ObjModel, (usize, String)> {
+    let mut model = ObjModel::default();
+    let mut material_names: HashMap<String, usize> = HashMap::new();
+    let mut current_material = None;
+    let mut current_group: Option<String> = None;
+    let mut group_start = 0;
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else { continue };
+        let args: Vec<&str> = tokens.collect();
+        let error = |reason: String| (line_number, reason);
+
+        match keyword {
+            "v" => model.positions.push(parse_floats(&args, 3).map(|f| Vec3::new(f[0], f[1], f[2])).map_err(error)?),
+            "vn" => model.normals.push(parse_floats(&args, 3).map(|f| Vec3::new(f[0], f[1], f[2]).normalize()).map_err(error)?),
+            "vt" => {
+                let f = parse_floats(&args[..args.len().min(2)], 1).map_err(error)?;
+                model.texcoords.push([f[0], f.get(1).copied().unwrap_or(0.0)]);
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error("A face needs at least 3 vertices".to_string()));
+                }
+                let corners = args.iter()
+                    .map(|corner| parse_corner(corner, &model))
+                    .collect::<Result<Vec<_>, String>>()
+                    .map_err(error)?;
+                // Découpage du polygone en éventail autour du premier sommet
+                for i in 1..corners.len() - 1 {
+                    model.faces.push(ObjFace { corners: [corners[0], corners[i], corners[i + 1]], material: current_material });
+                }
+            }
+            "g" | "o" => {
+                if let Some(name) = current_group.take() {
+                    model.groups.push(MeshGroup { name, faces: group_start..model.faces.len() });
+                }
+                current_group = Some(args.join(" "));
+                group_start = model.faces.len();
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                match material_names.get(&name) {
+                    Some(&index) => current_material = Some(index),
+                    None => return Err(error(format!("Unknown material '{}'", name))),
+                }
+            }
+            "mtllib" => {
+                for file in &args {
+                    let path = directory.join(file);
+                    for (name, material) in load_mtl(&path).map_err(error)? {
+                        material_names.insert(name.clone(), model.materials.len());
+                        model.materials.push((name, material));
+                    }
+                }
+            }
+            // Instructions sans effet sur le rendu (lissage, lignes, points, surfaces libres...)
+            _ => {}
+        }
+    }
+
+    if let Some(name) = current_group {
+        model.groups.push(MeshGroup { name, faces: group_start..model.faces.len() });
+    }
+
+    Ok(model)
+}
+
+impl ObjModel {
+    // Construit un maillage en transformant positions et normales ; seules les faces du groupe `group` sont gardées s'il est indiqué
+    pub fn to_mesh<P, N>(&self, group: Option<&str>, default_material: Material, position: P, normal: N) -> Result<Mesh, String>
+    where
+        P: Fn(Vec3) -> Vec3,
+        N: Fn(Vec3) -> Vec3,
+    {
+        let faces: Vec<usize> = match group {
+            Some(name) => {
+                let faces: Vec<usize> = self.groups.iter()
+                    .filter(|g| g.name == name)
+                    .flat_map(|g| g.faces.clone())
+                    .collect();
+                if faces.is_empty() {
+                    return Err(format!("No group or object named '{}' in the model", name));
+                }
+                faces
+            }
+            None => (0..self.faces.len()).collect(),
+        };
+
+        // Un sommet du maillage par combinaison (position, texture, normale) distincte
+        let mut vertex_ids: HashMap<(usize, Option<usize>, Option<usize>), usize> = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut texcoords = Vec::new();
+        let mut indices = Vec::new();
+        let mut face_materials = Vec::new();
+        // Les normales ne sont interpolées que si tous les sommets en ont une
+        let smooth = faces.iter().all(|&f| self.faces[f].corners.iter().all(|c| c.2.is_some()));
+        let textured = faces.iter().all(|&f| self.faces[f].corners.iter().all(|c| c.1.is_some()));
+
+        for &f in &faces {
+            let face = &self.faces[f];
+            let mut triangle = [0; 3];
+            for (k, &corner) in face.corners.iter().enumerate() {
+                triangle[k] = *vertex_ids.entry(corner).or_insert_with(|| {
+                    vertices.push(position(self.positions[corner.0]));
+                    if let Some(n) = corner.2 {
+                        normals.push(normal(self.normals[n]).normalize());
+                    }
+                    if let Some(t) = corner.1 {
+                        texcoords.push(self.texcoords[t]);
+                    }
+                    vertices.len() - 1
+                });
+            }
+            indices.push(triangle);
+            face_materials.push(face.material);
+        }
+
+        let mut mesh = Mesh::new(vertices, if smooth { Some(normals) } else { None }, indices, default_material)?;
+        if textured {
+            mesh.texcoords = Some(texcoords);
+        }
+        if face_materials.iter().any(|m| m.is_some()) {
+            let materials = self.materials.iter().map(|(_, m)| *m).collect();
+            mesh.set_face_materials(materials, face_materials)?;
+        }
+        mesh.groups = self.groups.clone();
+        if group.is_some() {
+            mesh.groups.clear();
+        }
+        Ok(mesh)
+    }
+}
+
+fn parse_floats(args: &[&str], min_count: usize) -> Result<Vec<f64>, String> {
+    if args.len() < min_count {
+        return Err(format!("Expected at least {} numbers", min_count));
+    }
+    args.iter()
+        .map(|a| a.parse::<f64>().map_err(|_| format!("Invalid number '{}'", a)))
+        .collect()
+}
+
+// Indice OBJ (à partir de 1, ou négatif pour compter depuis la fin) vers un indice Rust
+fn resolve_index(value: &str, count: usize, what: &str) -> Result<usize, String> {
+    let index: i64 = value.parse().map_err(|_| format!("Invalid {} index '{}'", what, value))?;
+    let resolved = if index > 0 { index - 1 } else { count as i64 + index };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(format!("{} index {} is out of range", what, index));
+    }
+    Ok(resolved as usize)
+}
+
+// Coin de face : "v", "v/vt", "v//vn" ou "v/vt/vn"
+fn parse_corner(corner: &str, model: &ObjModel) -> Result<(usize, Option<usize>, Option<usize>), String> {
+    let mut parts = corner.split('/');
+    let position = resolve_index(parts.next().unwrap_or(""), model.positions.len(), "vertex")?;
+    let texcoord = match parts.next() {
+        Some(t) if !t.is_empty() => Some(resolve_index(t, model.texcoords.len(), "texture coordinate")?),
+        _ => None,
+    };
+    let normal = match parts.next() {
+        Some(n) if !n.is_empty() => Some(resolve_index(n, model.normals.len(), "normal")?),
+        _ => None,
+    };
+    Ok((position, texcoord, normal))
+}
+
+// Lit les matériaux d'un fichier MTL et les convertit en matériaux du ray tracer
+pub fn load_mtl(path: &Path) -> Result<Vec<(String, Material)>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path.display(), e))?;
+    parse_mtl(&content).map_err(|(line, reason)| format!("{}:{}: {}", path.display(), line, reason))
+}
+
+pub fn parse_mtl(content: &str) -> Result<Vec<(String, Material)>, (usize, String)> {
+    let mut materials: Vec<(String, Material, Option<u32>)> = Vec::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else { continue };
+        let args: Vec<&str> = tokens.collect();
+        let error = |reason: String| (line_number, reason);
+
+        if keyword == "newmtl" {
+            materials.push((args.join(" "), Material::default(), None));
+            continue;
+        }
+        let Some((_, material, illum)) = materials.last_mut() else {
+            return Err(error(format!("'{}' before any newmtl", keyword)));
+        };
+
+        let color = |args: &[&str]| parse_floats(args, 3).map(|f| Color::new(f[0], f[1], f[2]));
+        let number = |args: &[&str]| parse_floats(args, 1).map(|f| f[0]);
+        match keyword {
+            "Kd" => material.diffuse = color(&args).map_err(error)?,
+            "Ks" => material.specular = color(&args).map_err(error)?,
+            "Ns" => material.shininess = number(&args).map_err(error)?.max(0.0),
+            "Ni" => material.ior = number(&args).map_err(error)?.max(1e-3),
+            "d" => material.transparency = (1.0 - number(&args).map_err(error)?).clamp(0.0, 1.0),
+            "Tr" => material.transparency = number(&args).map_err(error)?.clamp(0.0, 1.0),
+            "Ke" => material.emission = color(&args).map_err(error)?,
+            "illum" => *illum = Some(number(&args).map_err(error)? as u32),
+            // Propriétés sans équivalent dans le ray tracer (ambiante, textures, ...)
+            _ => {}
+        }
+    }
+
+    Ok(materials.into_iter().map(|(name, mut material, illum)| {
+        // Modèles d'éclairage avec réflexion miroir : la couleur spéculaire donne la réflectivité
+        if matches!(illum, Some(3) | Some(5) | Some(7)) {
+            let ks = material.specular;
+            material.reflectivity = ((ks.r + ks.g + ks.b) / 3.0).clamp(0.0, 1.0 - material.transparency);
+        }
+        (name, material)
+    }).collect())
+}
@@ -1,13 +1,27 @@
+use std::path::PathBuf;
+use std::sync::Arc;
 use crate::vec3::*;
-use crate::color::*;
+use crate::material::Material;
 use crate::ray::*;
+use crate::bvh::Aabb;
+use crate::utils::Params;
+use crate::mesh::*;
+use crate::obj::load_obj_mesh;
+use crate::transform::*;
+use crate::group::Group;
+use crate::primitives::*;
+use crate::csg::Csg;
+use crate::sdf::Sdf;
+use crate::heightfield::Heightfield;
 
 //Module pour les objets géométriques (sphère, plan, cube, etc.)
 #[derive(Debug)]
 pub struct Shape {
     pub shape_type: String,
-    pub color: String,
+    pub material: Material, // Matériau déjà résolu (nom de matériau ou couleur)
     pub location: Vec3,
+    pub directory: PathBuf, // Dossier du fichier de configuration, pour les chemins relatifs (file=...)
+    pub params: Params, // Paramètres optionnels "clé=valeur" (radius, length, ...)
 }
 
 #[derive(Debug)]
@@ -15,41 +29,86 @@ pub enum Object {
     Sphere(Sphere),
     Cube(Cube),
     FlatePlane(FlatePlane),
-    Cylinder(Cylinder)
+    Plane(Plane),
+    Quad(Box<Quad>),
+    Cylinder(Box<Cylinder>),
+    Triangle(Box<Triangle>),
+    Mesh(Box<Mesh>),
+    Cone(Box<Cone>),
+    Torus(Box<Torus>),
+    Capsule(Box<Capsule>),
+    Transformed(Box<Transformed>),
+    Group(Arc<Group>),
+    Csg(Box<Csg>),
+    Sdf(Box<Sdf>),
+    Heightfield(Box<Heightfield>),
 }
 
 impl Object {
-    pub fn new(shape: Shape) -> Result<Self, String> {
-        match shape.shape_type.as_str() {
-            "sphere" => Ok(Object::Sphere(Sphere::new(shape))),
-            "cube"=> Ok(Object::Cube(Cube::new(shape))),
-            "flateplane" => Ok(Object::FlatePlane(FlatePlane::new(shape))),
-            "cylinder" => Ok(Object::Cylinder(Cylinder::new(shape))),
-            _ => Err("Invalid shape type".to_string()),
+    pub fn new(mut shape: Shape) -> Result<Self, String> {
+        // rotation, scale et translate sont communs à toutes les formes et s'appliquent autour de leur position
+        let transform = Transform::from_params(&shape.params.split_off(&TRANSFORM_PARAMS))?;
+        let location = shape.location;
+
+        let object = match shape.shape_type.as_str() {
+            "sphere" => Object::Sphere(Sphere::new(shape)?),
+            "cube"=> Object::Cube(Cube::new(shape)?),
+            "flateplane" => Object::FlatePlane(FlatePlane::new(shape)?),
+            "plane" => Object::Plane(Plane::new(shape)?),
+            "quad" => Object::Quad(Box::new(Quad::new(shape)?)),
+            "cylinder" => Object::Cylinder(Box::new(Cylinder::new(shape)?)),
+            "triangle" => Object::Triangle(Box::new(Triangle::new(shape)?)),
+            "cone" => Object::Cone(Box::new(Cone::new(shape)?)),
+            "torus" => Object::Torus(Box::new(Torus::new(shape)?)),
+            "capsule" => Object::Capsule(Box::new(Capsule::new(shape)?)),
+            "heightfield" => Object::Heightfield(Box::new(Heightfield::new(shape)?)),
+            // Les sommets d'un modèle sont transformés une fois pour toutes au chargement
+            "obj" => return Ok(Object::Mesh(Box::new(load_obj_mesh(shape, transform.unwrap_or_else(Transform::identity))?))),
+            _ => return Err(format!("Invalid shape type '{}'", shape.shape_type)),
+        };
+
+        Ok(object.with_transform(transform, location))
+    }
+
+    // Place l'objet selon sa transformation éventuelle, appliquée autour du point `pivot`
+    pub fn with_transform(self, transform: Option<Transform>, pivot: Vec3) -> Object {
+        match transform {
+            Some(transform) => Object::Transformed(Box::new(Transformed::new(self, transform.around(pivot)))),
+            None => self,
         }
-    }  
+    }
 }
 
 pub trait Renderable {
     fn intersect(&self, ray: &Ray) -> Option<Intersection>;
-    fn color(&self) -> Color;
+    fn material(&self) -> Material;
+    fn bounding_box(&self) -> Aabb;
+}
+
+// Garde la plus proche de deux intersections (objets composés de plusieurs surfaces)
+pub fn closest_hit(current: Option<Intersection>, candidate: Option<Intersection>) -> Option<Intersection> {
+    match (current, candidate) {
+        (Some(current), Some(candidate)) => Some(if candidate.distance < current.distance { candidate } else { current }),
+        (current, candidate) => current.or(candidate),
+    }
 }
 
 #[derive(Debug)]
 pub struct Sphere {
     pub center: Vec3,
     pub radius: f64,
-    pub color: Color,
+    pub material: Material,
 }
 
 impl Sphere {
     //radius par défault 10
-    pub fn new(shape :Shape)->Self{
+    pub fn new(shape :Shape)->Result<Self, String>{
+        shape.params.check(&["radius"], &shape.shape_type)?;
         let center = shape.location;
-        let radius = 10.00;
-        let color = get_color(&shape.color);
+        let radius = shape.params.positive("radius", 10.00)?;
+        let material = shape.material;
 
-        Sphere {center, radius, color}
+        Ok(Sphere {center, radius, material})
     }
 
     pub fn normal_at(&self, point: Vec3) -> Vec3 {
@@ -61,16 +120,18 @@ impl Sphere {
 pub struct Cube {
     pub center: Vec3,
     pub length : f64,
-    pub color : Color
+    pub material : Material
 }
 
 impl Cube {
-    pub fn new(shape: Shape)-> Self {
+    //length (longueur d'une arête) par défaut 20
+    pub fn new(shape: Shape)-> Result<Self, String> {
+        shape.params.check(&["length"], &shape.shape_type)?;
         let center = shape.location;
-        let length = 20.00;
-        let color = get_color(&shape.color);
+        let length = shape.params.positive("length", 20.00)?;
+        let material = shape.material;
 
-        Cube {center, length, color}
+        Ok(Cube {center, length, material})
     }
 
     pub fn normal_at(&self, point: Vec3) -> Vec3 {
@@ -96,50 +157,118 @@ impl Cube {
 pub struct FlatePlane {
     pub center: Vec3,
     pub normal: Vec3,
-    pub color: Color,
+    pub material: Material,
     pub heigth: f64,
     pub width: f64,
 }
 
 impl FlatePlane {
-    pub fn new(shape: Shape) -> Self {
+    //width (selon x) et height (selon z) par défaut 200, normal par défaut (0,1,0)
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["width", "height", "normal"], &shape.shape_type)?;
         let center = shape.location;
-        let normal = Vec3::new(0.0, 1.0, 0.0);
-        let color = get_color(&shape.color);
-        let heigth = 200.00;
-        let width = 200.00;
+        let normal = shape.params.vec3("normal", Vec3::new(0.0, 1.0, 0.0))?;
+        if normal.length() < 1e-12 {
+            return Err("Parameter 'normal' must not be a null vector".to_string());
+        }
+        let normal = normal.normalize();
+        let material = shape.material;
+        let heigth = shape.params.positive("height", 200.00)?;
+        let width = shape.params.positive("width", 200.00)?;
 
-        FlatePlane { center, normal, color,    heigth, width }
+        Ok(FlatePlane { center, normal, material, heigth, width })
     }
 
     pub fn normal_at(&self, _point: Vec3) -> Vec3 {
         self.normal
     }
-    
+
+    // Directions de la largeur et de la hauteur dans le plan (x et z pour la normale par défaut)
+    pub fn axes(&self) -> (Vec3, Vec3) {
+        self.normal.orthonormal_basis()
+    }
+}
+
+// Plan infini passant par sa position, de normale quelconque
+#[derive(Debug)]
+pub struct Plane {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub material: Material,
+}
+
+impl Plane {
+    //normal par défaut (0,1,0)
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["normal"], &shape.shape_type)?;
+        let normal = shape.params.vec3("normal", Vec3::new(0.0, 1.0, 0.0))?;
+        if normal.length() < 1e-12 {
+            return Err("Parameter 'normal' must not be a null vector".to_string());
+        }
+
+        Ok(Plane { point: shape.location, normal: normal.normalize(), material: shape.material })
+    }
+}
+
+// Parallélogramme défini par un coin (la position) et deux arêtes `u` et `v` ;
+// sa face avant est du côté de u × v
+#[derive(Debug)]
+pub struct Quad {
+    pub corner: Vec3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub normal: Vec3,
+    pub material: Material,
+}
+
+impl Quad {
+    //u par défaut (20,0,0), v par défaut (0,0,-20) : carré horizontal tourné vers le haut
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["u", "v"], &shape.shape_type)?;
+        let u = shape.params.vec3("u", Vec3::new(20.0, 0.0, 0.0))?;
+        let v = shape.params.vec3("v", Vec3::new(0.0, 0.0, -20.0))?;
+        let normal = u.cross(v);
+        if normal.length() < 1e-12 {
+            return Err("The edges 'u' and 'v' of the quad must not be parallel".to_string());
+        }
+
+        Ok(Quad { corner: shape.location, u, v, normal: normal.normalize(), material: shape.material })
+    }
 }
 
 #[derive(Debug)]
 pub struct Cylinder {
-    pub center: Vec3,
+    pub center: Vec3,   // Centre de la base (un point de l'axe pour un cylindre infini)
     pub radius: f64,
-    pub height: f64,
-    pub color: Color,
+    pub height: f64,    // Infinie pour un cylindre sans fin dans les deux sens
+    pub capped: bool,   // Faux : cylindre ouvert, sans disques aux extrémités
+    pub material: Material,
     pub top_disk: Disk,
     pub bottom_disk: Disk,
 }
 
 impl Cylinder {
-    pub fn new(shape: Shape) -> Self {
+    //radius par défaut 10, height par défaut 20 (ou inf), caps par défaut closed
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["radius", "height", "caps"], &shape.shape_type)?;
         let center = shape.location;
-        let radius = 10.00;
-        let height = 20.00;
-        let color = get_color(&shape.color);
+        let radius = shape.params.positive("radius", 10.00)?;
+        let height = shape.params.positive("height", 20.00)?;
+        let material = shape.material;
+
+        let capped = match shape.params.get("caps") {
+            None => height.is_finite(),
+            Some("closed") if height.is_finite() => true,
+            Some("closed") => return Err("An infinite cylinder cannot have caps".to_string()),
+            Some("open") => false,
+            Some(value) => return Err(format!("Unknown value '{}' for parameter 'caps', expected open or closed", value)),
+        };
 
-        let top_center = center + Vec3::new(0.0, height, 0.0);
-        let top_disk = Disk::new(top_center, Vec3::new(0.0, 1.0, 0.0), radius, color);
-        let bottom_disk = Disk::new(center, Vec3::new(0.0, -1.0, 0.0), radius, color);
+        let top_center = center + Vec3::new(0.0, if height.is_finite() { height } else { 0.0 }, 0.0);
+        let top_disk = Disk::new(top_center, Vec3::new(0.0, 1.0, 0.0), radius, material);
+        let bottom_disk = Disk::new(center, Vec3::new(0.0, -1.0, 0.0), radius, material);
 
-        Cylinder { center, radius, height, color, top_disk, bottom_disk }
+        Ok(Cylinder { center, radius, height, capped, material, top_disk, bottom_disk })
     }
 
     pub fn normal_at(&self, point: Vec3) -> Vec3 {
@@ -147,7 +276,11 @@ impl Cylinder {
         normal.y = 0.0;
         normal.normalize()
     }
-    
+
+    // Vrai si le point de la surface latérale est entre la base et le sommet
+    fn within_height(&self, point: Vec3) -> bool {
+        !self.height.is_finite() || (point.y >= self.center.y && point.y <= self.center.y + self.height)
+    }
 }
 
 impl Renderable for Object {
@@ -157,15 +290,60 @@ impl Renderable for Object {
             Object::Cube(cube ) => cube.intersect(ray),
             Object::FlatePlane(flateplane) => flateplane.intersect(ray),
             Object::Cylinder(cylinder) => cylinder.intersect(ray),
+            Object::Triangle(triangle) => triangle.intersect(ray),
+            Object::Mesh(mesh) => mesh.intersect(ray),
+            Object::Plane(plane) => plane.intersect(ray),
+            Object::Quad(quad) => quad.intersect(ray),
+            Object::Cone(cone) => cone.intersect(ray),
+            Object::Torus(torus) => torus.intersect(ray),
+            Object::Capsule(capsule) => capsule.intersect(ray),
+            Object::Transformed(transformed) => transformed.intersect(ray),
+            Object::Group(group) => group.intersect(ray),
+            Object::Csg(csg) => csg.intersect(ray),
+            Object::Sdf(sdf) => sdf.intersect(ray),
+            Object::Heightfield(heightfield) => heightfield.intersect(ray),
         }
     }
 
-    fn color(&self) -> Color {
+    fn material(&self) -> Material {
         match self {
-            Object::Sphere(sphere) => sphere.color(),
-            Object::Cube(cube)=> cube.color(),
-            Object::FlatePlane(flateplane) => flateplane.color(),
-            Object::Cylinder(cylinder) => cylinder.color()
+            Object::Sphere(sphere) => sphere.material(),
+            Object::Cube(cube)=> cube.material(),
+            Object::FlatePlane(flateplane) => flateplane.material(),
+            Object::Cylinder(cylinder) => cylinder.material(),
+            Object::Triangle(triangle) => triangle.material(),
+            Object::Mesh(mesh) => mesh.material(),
+            Object::Plane(plane) => plane.material(),
+            Object::Quad(quad) => quad.material(),
+            Object::Cone(cone) => cone.material(),
+            Object::Torus(torus) => torus.material(),
+            Object::Capsule(capsule) => capsule.material(),
+            Object::Transformed(transformed) => transformed.material(),
+            Object::Group(group) => group.material(),
+            Object::Csg(csg) => csg.material(),
+            Object::Sdf(sdf) => sdf.material(),
+            Object::Heightfield(heightfield) => heightfield.material(),
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match self {
+            Object::Sphere(sphere) => sphere.bounding_box(),
+            Object::Cube(cube) => cube.bounding_box(),
+            Object::FlatePlane(flateplane) => flateplane.bounding_box(),
+            Object::Cylinder(cylinder) => cylinder.bounding_box(),
+            Object::Triangle(triangle) => triangle.bounding_box(),
+            Object::Mesh(mesh) => mesh.bounding_box(),
+            Object::Plane(plane) => plane.bounding_box(),
+            Object::Quad(quad) => quad.bounding_box(),
+            Object::Cone(cone) => cone.bounding_box(),
+            Object::Torus(torus) => torus.bounding_box(),
+            Object::Capsule(capsule) => capsule.bounding_box(),
+            Object::Transformed(transformed) => transformed.bounding_box(),
+            Object::Group(group) => group.bounding_box(),
+            Object::Csg(csg) => csg.bounding_box(),
+            Object::Sdf(sdf) => sdf.bounding_box(),
+            Object::Heightfield(heightfield) => heightfield.bounding_box(),
         }
     }
 }
@@ -210,14 +388,19 @@ impl Renderable for Sphere {
             let normal = (point - self.center).normalize();
 
             //si tous est ok renvoie une intersection
-            return Some(Intersection { point, normal, distance: t, color: self.color });
+            return Some(Intersection { point, normal, distance: t, material: self.material });
         }
 
         None
     }
 
-    fn color(&self) -> Color {
-        self.color
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center - r, self.center + r)
     }
 }
 
@@ -266,11 +449,17 @@ impl Renderable for Cube {
             Vec3::new(0.0, 0.0, (point.z - self.center.z).signum())
         };
 
-        Some(Intersection { point, normal, distance: t, color: self.color })
+        Some(Intersection { point, normal, distance: t, material: self.material })
     }
 
-    fn color(&self) -> Color {
-        self.color
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let half_length = self.length / 2.0;
+        let h = Vec3::new(half_length, half_length, half_length);
+        Aabb::new(self.center - h, self.center + h)
     }
 }
 
@@ -281,10 +470,10 @@ impl Renderable for FlatePlane {
             let t = (self.center - ray.origin).dot(self.normal) / denom;
             if t > 0.0 {
                 let point = ray.origin + ray.direction * t;
-                let half_width = self.width / 2.0;
-                let half_height = self.heigth / 2.0;
-                if (point.x - self.center.x).abs() <= half_width && (point.z - self.center.z).abs() <= half_height {
-                    return Some(Intersection { point, normal: self.normal, distance: t, color: self.color });
+                let (width_axis, height_axis) = self.axes();
+                let offset = point - self.center;
+                if offset.dot(width_axis).abs() <= self.width / 2.0 && offset.dot(height_axis).abs() <= self.heigth / 2.0 {
+                    return Some(Intersection { point, normal: self.normal, distance: t, material: self.material });
                 }
             }
         }
@@ -292,53 +481,120 @@ impl Renderable for FlatePlane {
         None
     }
 
-    fn color(&self) -> Color {
-        self.color
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let (width_axis, height_axis) = self.axes();
+        let (w, h) = (width_axis * (self.width / 2.0), height_axis * (self.heigth / 2.0));
+        Aabb::new(self.center - w - h, self.center + w + h)
+            .union(&Aabb::new(self.center - w + h, self.center + w - h))
     }
     
 }
 
+impl Renderable for Plane {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let denom = self.normal.dot(ray.direction);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = (self.point - ray.origin).dot(self.normal) / denom;
+        if t <= 0.0 {
+            return None;
+        }
+        Some(Intersection { point: ray.at(t), normal: self.normal, distance: t, material: self.material })
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::infinite()
+    }
+}
+
+impl Renderable for Quad {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let n = self.u.cross(self.v);
+        let denom = n.dot(ray.direction);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = (self.corner - ray.origin).dot(n) / denom;
+        if t <= 0.0 {
+            return None;
+        }
+
+        // Coordonnées du point dans la base (u, v) : il est dans le quad si les deux sont entre 0 et 1
+        let point = ray.at(t);
+        let q = point - self.corner;
+        let w = n / n.dot(n);
+        let alpha = w.dot(q.cross(self.v));
+        let beta = w.dot(self.u.cross(q));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(Intersection { point, normal: self.normal, distance: t, material: self.material })
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(self.corner, self.corner + self.u + self.v)
+            .union(&Aabb::new(self.corner + self.u, self.corner + self.v))
+    }
+}
+
 impl Renderable for Cylinder {
     fn intersect(&self, ray: &Ray) -> Option<Intersection> {
-        // Intersect with the cylindrical surface
-        let oc = ray.origin - self.center;
+        let mut closest = None;
 
+        // Surface latérale : les deux solutions sont testées, la plus proche peut être hors de la hauteur
+        let oc = ray.origin - self.center;
         let a = ray.direction.x * ray.direction.x + ray.direction.z * ray.direction.z;
         let b = 2.0 * oc.x * ray.direction.x + 2.0 * oc.z * ray.direction.z;
         let c = oc.x * oc.x + oc.z * oc.z - self.radius * self.radius;
-
         let discriminant = b * b - 4.0 * a * c;
 
-        if discriminant > 0.0 {
+        // a nul : rayon parallèle à l'axe, qui ne peut toucher que les disques
+        if a > 1e-12 && discriminant > 0.0 {
             let sqrt_discriminant = discriminant.sqrt();
-            let t1 = (-b - sqrt_discriminant) / (2.0 * a);
-            let t2 = (-b + sqrt_discriminant) / (2.0 * a);
-
-            let t = if t1 > 0.0 && t2 > 0.0 {
-                t1.min(t2)
-            } else if t1 > 0.0 {
-                t1
-            } else if t2 > 0.0 {
-                t2
-            } else {
-                return None;
-            };
-
-            let point = ray.origin + ray.direction * t;
-            if point.y >= self.center.y && point.y <= self.center.y + self.height {
-                return Some(Intersection { point, normal: self.normal_at(point), distance: t, color: self.color });
+            for t in [(-b - sqrt_discriminant) / (2.0 * a), (-b + sqrt_discriminant) / (2.0 * a)] {
+                if t <= 0.0 {
+                    continue;
+                }
+                let point = ray.origin + ray.direction * t;
+                if self.within_height(point) {
+                    closest = closest_hit(closest, Some(Intersection { point, normal: self.normal_at(point), distance: t, material: self.material }));
+                }
             }
         }
 
-        // Intersect with the top and bottom disks
-        let top_intersection = self.top_disk.intersect(ray);
-        let bottom_intersection = self.bottom_disk.intersect(ray);
+        if self.capped {
+            closest = closest_hit(closest, self.top_disk.intersect(ray));
+            closest = closest_hit(closest, self.bottom_disk.intersect(ray));
+        }
+
+        closest
+    }
 
-        top_intersection.or(bottom_intersection)
+    fn material(&self) -> Material {
+        self.material
     }
 
-    fn color(&self) -> Color {
-        self.color
+    fn bounding_box(&self) -> Aabb {
+        if !self.height.is_finite() {
+            return Aabb::infinite();
+        }
+        let min = self.center - Vec3::new(self.radius, 0.0, self.radius);
+        let max = self.center + Vec3::new(self.radius, self.height, self.radius);
+        Aabb::new(min, max)
     }
 }
 
@@ -347,12 +603,12 @@ pub struct Disk {
     pub center: Vec3,
     pub normal: Vec3,
     pub radius: f64,
-    pub color: Color,
+    pub material: Material,
 }
 
 impl Disk {
-    pub fn new(center: Vec3, normal: Vec3, radius: f64, color: Color) -> Self {
-        Disk { center, normal, radius, color }
+    pub fn new(center: Vec3, normal: Vec3, radius: f64, material: Material) -> Self {
+        Disk { center, normal, radius, material }
     }
 
     pub fn normal_at(&self, _point: Vec3) -> Vec3 {
@@ -368,7 +624,7 @@ impl Renderable for Disk {
             if t > 0.0 {
                 let point = ray.origin + ray.direction * t;
                 if (point - self.center).length() <= self.radius {
-                    return Some(Intersection { point, normal: self.normal, distance: t, color: self.color });
+                    return Some(Intersection { point, normal: self.normal, distance: t, material: self.material });
                 }
             }
         }
@@ -376,7 +632,144 @@ impl Renderable for Disk {
         None
     }
 
-    fn color(&self) -> Color {
-        self.color
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Étendue du disque sur chaque axe : radius * sqrt(1 - n²)
+        let n = self.normal.normalize();
+        let e = Vec3::new(
+            self.radius * (1.0 - n.x * n.x).max(0.0).sqrt(),
+            self.radius * (1.0 - n.y * n.y).max(0.0).sqrt(),
+            self.radius * (1.0 - n.z * n.z).max(0.0).sqrt(),
+        );
+        Aabb::new(self.center - e, self.center + e)
+    }
+}
+
+// Objet placé dans la scène par une transformation : les rayons sont ramenés dans l'espace de l'objet,
+// puis le point et la normale touchés sont ramenés dans l'espace de la scène
+#[derive(Debug)]
+pub struct Transformed {
+    pub object: Object,
+    pub transform: Transform,
+}
+
+impl Transformed {
+    pub fn new(object: Object, transform: Transform) -> Self {
+        Transformed { object, transform }
+    }
+}
+
+impl Renderable for Transformed {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let local_ray = self.transform.inverse_ray(ray);
+        let hit = self.object.intersect(&local_ray)?;
+        Some(Intersection {
+            point: ray.at(hit.distance),
+            normal: self.transform.normal(hit.normal),
+            distance: hit.distance,
+            material: hit.material,
+        })
+    }
+
+    fn material(&self) -> Material {
+        self.object.material()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.transform.bounding_box(&self.object.bounding_box())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(params: &[&str]) -> Shape {
+        Shape {
+            shape_type: "cylinder".to_string(),
+            material: Material::default(),
+            location: Vec3::new(0.0, 0.0, 0.0),
+            directory: PathBuf::new(),
+            params: Params::parse(params).unwrap(),
+        }
+    }
+
+    fn cylinder(params: &[&str]) -> Cylinder {
+        Cylinder::new(shape(params)).unwrap()
+    }
+
+    fn hit(cylinder: &Cylinder, origin: Vec3, direction: Vec3) -> Option<Intersection> {
+        cylinder.intersect(&Ray::new(origin, direction.normalize()))
+    }
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-6, "expected {:?}, found {:?}", b, a);
+    }
+
+    #[test]
+    fn side_hit_from_outside() {
+        let cylinder = cylinder(&["radius=1", "height=2"]);
+        let intersection = hit(&cylinder, Vec3::new(-5.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        assert!((intersection.distance - 4.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn bottom_cap_is_nearer_than_top_cap() {
+        let cylinder = cylinder(&["radius=1", "height=2"]);
+        let intersection = hit(&cylinder, Vec3::new(0.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+        assert!((intersection.distance - 5.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn cap_is_nearer_than_side_behind_it() {
+        // Le rayon entre par le disque du haut et ressort par la surface latérale
+        let cylinder = cylinder(&["radius=1", "height=2"]);
+        let intersection = hit(&cylinder, Vec3::new(0.0, 3.0, 0.0), Vec3::new(0.5, -1.0, 0.0)).unwrap();
+        assert_close(intersection.point, Vec3::new(0.5, 2.0, 0.0));
+        assert_close(intersection.normal, Vec3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn open_cylinder_is_seen_through_its_ends() {
+        let cylinder = cylinder(&["radius=1", "height=2", "caps=open"]);
+        let intersection = hit(&cylinder, Vec3::new(0.0, 3.0, 0.0), Vec3::new(0.5, -1.0, 0.0)).unwrap();
+        assert_close(intersection.point, Vec3::new(1.0, 1.0, 0.0));
+
+        assert!(hit(&cylinder, Vec3::new(0.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn far_side_is_hit_from_inside() {
+        let cylinder = cylinder(&["radius=1", "height=2"]);
+        let intersection = hit(&cylinder, Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)).unwrap();
+        assert!((intersection.distance - 1.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn side_outside_height_is_missed() {
+        let cylinder = cylinder(&["radius=1", "height=2"]);
+        assert!(hit(&cylinder, Vec3::new(-5.0, 3.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+        assert!(hit(&cylinder, Vec3::new(-5.0, -1.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn infinite_cylinder_has_no_end() {
+        let cylinder = cylinder(&["radius=1", "height=inf"]);
+        let intersection = hit(&cylinder, Vec3::new(-5.0, -1000.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        assert!((intersection.distance - 4.0).abs() < 1e-6);
+        assert!(hit(&cylinder, Vec3::new(0.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).is_none());
+        assert!(!cylinder.bounding_box().is_finite());
+    }
+
+    #[test]
+    fn invalid_caps_are_rejected() {
+        assert!(Cylinder::new(shape(&["caps=half"])).is_err());
+        assert!(Cylinder::new(shape(&["height=inf", "caps=closed"])).is_err());
     }
 }
@@ -0,0 +1,160 @@
+//Module pour le rendu par tracé de chemins (path tracing) : éclairage indirect et échanges de couleur entre surfaces
+
+use std::f64::consts::PI;
+use crate::color::*;
+use crate::vec3::Vec3;
+use crate::ray::*;
+use crate::scene::SceneParams;
+use crate::sampler::Sampler;
+use crate::light::LightKind;
+use crate::emitter::is_emissive;
+
+// Nombre maximal de rebonds d'un chemin, au-delà de la roulette russe
+const MAX_BOUNCES: u32 = 64;
+// Nombre de rebonds avant d'appliquer la roulette russe
+const ROULETTE_DEPTH: u32 = 3;
+
+// Couleur vue par un rayon de caméra, estimée par un chemin aléatoire.
+// À chaque rebond, une seule composante du matériau est choisie au hasard selon son poids : transparence,
+// réflexion miroir, microfacettes ou diffusion. Sur une surface diffuse ou à microfacettes, chaque lumière est échantillonnée directement (next-event
+// estimation) ; les lumières surfaciques peuvent aussi être touchées par le rebond suivant, et les deux estimations
+// sont combinées par échantillonnage multiple préférentiel (MIS, heuristique des puissances), de même que les objets émissifs.
+pub fn path_color(camera_ray: &Ray, scene: &SceneParams, sampler: &mut Sampler) -> Color {
+    let mut radiance = get_color("black");
+    let mut throughput = get_color("white");
+    let mut ray = Ray::new(camera_ray.origin, camera_ray.direction.normalize());
+    // Densité du dernier rebond diffus ou sur des microfacettes, pour pondérer les lumières surfaciques touchées ensuite
+    // (None pour le rayon de caméra et après un miroir : la lumière n'a alors pas été échantillonnée)
+    let mut bounce_pdf: Option<f64> = None;
+
+    for bounce in 0..MAX_BOUNCES {
+        let intersection = scene.intersect(&ray);
+        let surface_distance = intersection.as_ref().map_or(f64::INFINITY, |hit| hit.distance);
+
+        // Lumières surfaciques touchées avant la surface suivante : vues directement, dans un miroir, à travers
+        // du verre, ou par un rebond diffus ou sur des microfacettes, pondéré alors par MIS
+        for light in &scene.lights {
+            let Some(sample) = light.hit(ray.origin, ray.direction) else { continue };
+            if sample.distance < surface_distance {
+                // Même estimation que l'échantillonnage de la lumière, rapportée à la densité du rebond
+                let weight = bounce_pdf.map_or(1.0, |bsdf_pdf| power_heuristic(bsdf_pdf, sample.pdf));
+                radiance = radiance + throughput * sample.radiance * (PI * sample.pdf * weight);
+            }
+        }
+
+        let Some(intersection) = intersection else {
+            radiance = radiance + throughput * scene.background_color;
+            break;
+        };
+
+        let material = &intersection.material;
+        if is_emissive(&material.emission) {
+            // Après un rebond diffus ou sur des microfacettes, un objet émissif échantillonné comme lumière est pondéré par MIS comme ci-dessus
+            let weight = bounce_pdf.map_or(1.0, |bsdf_pdf| {
+                scene.lights.iter()
+                    .find_map(|light| match &light.kind {
+                        LightKind::Emitter(emitter) if emitter.is_hit(&scene.objects, &ray, &intersection) => Some(emitter.pdf(ray.origin, &intersection)),
+                        _ => None,
+                    })
+                    .map_or(1.0, |light_pdf| power_heuristic(bsdf_pdf, light_pdf))
+            });
+            radiance = radiance + throughput * material.emission * weight;
+        }
+
+        let entering = ray.direction.dot(intersection.normal) < 0.0;
+        let facing_normal = if entering { intersection.normal } else { -intersection.normal };
+        let choice = sampler.next_f64();
+
+        if choice < material.transparency {
+            // Diélectrique : réflexion ou réfraction, choisie selon le coefficient de Fresnel
+            let eta = if entering { 1.0 / material.ior } else { material.ior };
+            let cos_incident = -ray.direction.dot(facing_normal);
+            let refracted = ray.direction.refract(facing_normal, eta)
+                .filter(|_| sampler.next_f64() >= schlick(cos_incident, eta));
+            ray = match refracted {
+                Some(direction) => Ray::new(intersection.point - facing_normal * EPSILON, direction),
+                None => Ray::new(intersection.point + facing_normal * EPSILON, ray.direction.reflect(facing_normal).normalize()),
+            };
+            bounce_pdf = None;
+        } else if choice < material.transparency + material.reflectivity {
+            ray = Ray::new(intersection.point + facing_normal * EPSILON, ray.direction.reflect(facing_normal).normalize());
+            bounce_pdf = None;
+        } else if let Some(microfacet) = &material.microfacet {
+            let origin = intersection.point + facing_normal * EPSILON;
+            let view = -ray.direction;
+
+            // Éclairage direct, pondéré par MIS face à l'échantillonnage des microfacettes
+            for light in &scene.lights {
+                let Some(sample) = light.sample(intersection.point, sampler.next_2d()) else { continue };
+                if sample.direction.dot(facing_normal) <= 0.0 || scene.occluded(&Ray::new(origin, sample.direction), sample.distance) {
+                    continue;
+                }
+                let weight = if light.is_delta() || sample.pdf == 0.0 {
+                    1.0
+                } else {
+                    power_heuristic(sample.pdf, microfacet.pdf(facing_normal, view, sample.direction))
+                };
+                radiance = radiance + throughput * microfacet.eval(facing_normal, view, sample.direction) * sample.radiance * weight;
+            }
+
+            // Rebond tiré selon le reflet ou la diffusion ; la réponse (BRDF × π × cosinus) est divisée par π × densité
+            let Some(direction) = microfacet.sample(facing_normal, view, sampler.next_f64(), sampler.next_2d()) else { break };
+            let pdf = microfacet.pdf(facing_normal, view, direction);
+            if pdf <= 0.0 {
+                break;
+            }
+            bounce_pdf = Some(pdf);
+            throughput = throughput * microfacet.eval(facing_normal, view, direction) * (1.0 / (PI * pdf));
+            ray = Ray::new(origin, direction);
+        } else {
+            let origin = intersection.point + facing_normal * EPSILON;
+
+            // Éclairage direct : un échantillon par lumière, pondéré par MIS pour les lumières surfaciques
+            for light in &scene.lights {
+                let Some(sample) = light.sample(intersection.point, sampler.next_2d()) else { continue };
+                let cos = sample.direction.dot(facing_normal);
+                if cos <= 0.0 || scene.occluded(&Ray::new(origin, sample.direction), sample.distance) {
+                    continue;
+                }
+                let weight = if light.is_delta() || sample.pdf == 0.0 { 1.0 } else { power_heuristic(sample.pdf, cos / PI) };
+                radiance = radiance + throughput * material.diffuse * sample.radiance * (cos * weight);
+
+                // Reflet de Blinn-Phong, estimé seulement par l'échantillonnage des lumières
+                let half_vector = (sample.direction - ray.direction).normalize();
+                let specular_intensity = half_vector.dot(facing_normal).max(0.0).powf(material.shininess);
+                radiance = radiance + throughput * material.specular * sample.radiance * specular_intensity;
+            }
+
+            // Rebond diffus : direction tirée selon le cosinus, dont la densité se simplifie avec la BRDF de Lambert
+            let direction = cosine_hemisphere(facing_normal, sampler.next_2d());
+            bounce_pdf = Some(direction.dot(facing_normal).max(0.0) / PI);
+            throughput = throughput * material.diffuse;
+            ray = Ray::new(origin, direction);
+        }
+
+        // Roulette russe : les chemins qui transportent peu d'énergie sont interrompus, les autres renforcés d'autant
+        if bounce >= ROULETTE_DEPTH {
+            let survival = throughput.r.max(throughput.g).max(throughput.b).clamp(0.05, 0.95);
+            if sampler.next_f64() >= survival {
+                break;
+            }
+            throughput = throughput * (1.0 / survival);
+        }
+    }
+
+    radiance
+}
+
+// Direction tirée dans l'hémisphère autour de la normale, avec une densité proportionnelle au cosinus
+pub fn cosine_hemisphere(normal: Vec3, random: (f64, f64)) -> Vec3 {
+    let (first, second) = normal.orthonormal_basis();
+    let (r, phi) = (random.0.sqrt(), 2.0 * PI * random.1);
+    let height = (1.0 - random.0).max(0.0).sqrt();
+    (first * (r * phi.cos()) + second * (r * phi.sin()) + normal * height).normalize()
+}
+
+// Poids de l'estimation de densité `pdf` face à une autre stratégie de densité `other`
+fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other * other);
+    if a + b == 0.0 { 0.0 } else { a / (a + b) }
+}
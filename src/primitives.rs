@@ -0,0 +1,423 @@
+//Module pour les primitives supplémentaires (cône, tore, capsule)
+
+use crate::vec3::*;
+use crate::ray::*;
+use crate::bvh::Aabb;
+use crate::material::Material;
+use crate::objects::*;
+use crate::solver::*;
+
+// Cône d'axe vertical (+Y), éventuellement tronqué : le rayon passe de `radius` à la base
+// à `top_radius` au sommet (0 pour un cône pointu)
+#[derive(Debug)]
+pub struct Cone {
+    pub center: Vec3,   // Centre de la base
+    pub radius: f64,
+    pub top_radius: f64,
+    pub height: f64,
+    pub capped: bool,   // Faux : cône ouvert, sans disques aux extrémités
+    pub material: Material,
+    pub bottom_disk: Disk,
+    pub top_disk: Option<Disk>, // Seulement pour un cône tronqué
+}
+
+impl Cone {
+    //radius par défaut 10, top_radius par défaut 0, height par défaut 20, caps par défaut closed
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["radius", "top_radius", "height", "caps"], &shape.shape_type)?;
+        let center = shape.location;
+        let radius = shape.params.positive("radius", 10.0)?;
+        let top_radius = shape.params.number("top_radius", 0.0)?;
+        let height = shape.params.positive("height", 20.0)?;
+        let material = shape.material;
+
+        if top_radius < 0.0 {
+            return Err(format!("Parameter 'top_radius' must not be negative, found {}", top_radius));
+        }
+        if !height.is_finite() {
+            return Err("Parameter 'height' must be finite".to_string());
+        }
+        let capped = match shape.params.get("caps") {
+            None | Some("closed") => true,
+            Some("open") => false,
+            Some(value) => return Err(format!("Unknown value '{}' for parameter 'caps', expected open or closed", value)),
+        };
+
+        let bottom_disk = Disk::new(center, Vec3::new(0.0, -1.0, 0.0), radius, material);
+        let top_disk = (top_radius > 0.0)
+            .then(|| Disk::new(center + Vec3::new(0.0, height, 0.0), Vec3::new(0.0, 1.0, 0.0), top_radius, material));
+
+        Ok(Cone { center, radius, top_radius, height, capped, material, bottom_disk, top_disk })
+    }
+
+    // Variation du rayon par unité de hauteur
+    fn slope(&self) -> f64 {
+        (self.top_radius - self.radius) / self.height
+    }
+
+    // Distances où la droite du rayon traverse la surface latérale (y compris derrière l'origine), triées
+    pub fn side_roots(&self, ray: &Ray) -> Vec<f64> {
+        let oc = ray.origin - self.center;
+        let d = ray.direction;
+        let k = self.slope();
+        let base = self.radius + k * oc.y;
+
+        let a = d.x * d.x + d.z * d.z - k * k * d.y * d.y;
+        let b = 2.0 * (oc.x * d.x + oc.z * d.z - k * base * d.y);
+        let c = oc.x * oc.x + oc.z * oc.z - base * base;
+
+        // La surface d'équation x² + z² = r(y)² contient aussi le cône opposé, au-delà du sommet
+        let mut roots = solve_quadratic(a, b, c);
+        roots.retain(|&t| (0.0..=self.height).contains(&(ray.at(t).y - self.center.y)));
+        roots
+    }
+
+    pub fn normal_at(&self, point: Vec3) -> Vec3 {
+        // Gradient de x² + z² - (radius + slope·y)²
+        let local = point - self.center;
+        let radius_at = self.radius + self.slope() * local.y;
+        Vec3::new(local.x, -self.slope() * radius_at, local.z).normalize()
+    }
+}
+
+impl Renderable for Cone {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let mut closest = None;
+        if let Some(t) = self.side_roots(ray).into_iter().find(|&t| t > 0.0) {
+            let point = ray.at(t);
+            closest = Some(Intersection { point, normal: self.normal_at(point), distance: t, material: self.material });
+        }
+
+        if self.capped {
+            closest = closest_hit(closest, self.bottom_disk.intersect(ray));
+            if let Some(top_disk) = &self.top_disk {
+                closest = closest_hit(closest, top_disk.intersect(ray));
+            }
+        }
+
+        closest
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.radius.max(self.top_radius);
+        Aabb::new(self.center - Vec3::new(r, 0.0, r), self.center + Vec3::new(r, self.height, r))
+    }
+}
+
+// Tore centré sur sa position, dans le plan horizontal (axe +Y)
+#[derive(Debug)]
+pub struct Torus {
+    pub center: Vec3,
+    pub radius: f64,    // Rayon du cercle central du tube
+    pub tube: f64,      // Rayon du tube
+    pub material: Material,
+}
+
+impl Torus {
+    //radius par défaut 10, tube par défaut 3
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["radius", "tube"], &shape.shape_type)?;
+        let center = shape.location;
+        let radius = shape.params.positive("radius", 10.0)?;
+        let tube = shape.params.positive("tube", 3.0)?;
+        if !radius.is_finite() || !tube.is_finite() {
+            return Err("Parameters 'radius' and 'tube' must be finite".to_string());
+        }
+
+        Ok(Torus { center, radius, tube, material: shape.material })
+    }
+
+    // Distances de tous les points où la droite du rayon traverse le tore (y compris derrière l'origine), triées
+    pub fn roots(&self, ray: &Ray) -> Vec<f64> {
+        // Calcul dans un repère où le grand rayon vaut 1 et la direction est unitaire, pour le conditionnement de la quartique
+        let scale = self.radius;
+        let direction_length = ray.direction.length();
+        let d = ray.direction / direction_length;
+        let origin = (ray.origin - self.center) / scale;
+        let tube = self.tube / scale;
+
+        // Le rayon est d'abord ramené à l'entrée de la sphère englobante : des coefficients plus petits donnent des racines plus précises
+        let bound = 1.0 + tube;
+        let entry = solve_quadratic(1.0, 2.0 * origin.dot(d), origin.dot(origin) - bound * bound);
+        let Some(&shift) = entry.first() else {
+            return Vec::new();
+        };
+        let o = origin + d * shift;
+
+        // (|o + s·d|² + 1 - tube²)² = 4·((o.x + s·d.x)² + (o.z + s·d.z)²)
+        let f = o.dot(d);
+        let e = o.dot(o) - 1.0 - tube * tube;
+        let roots = solve_quartic(
+            4.0 * f,
+            2.0 * e + 4.0 * f * f + 4.0 * d.y * d.y,
+            4.0 * f * e + 8.0 * o.y * d.y,
+            e * e - 4.0 * (tube * tube - o.y * o.y),
+        );
+
+        roots.into_iter().map(|s| (s + shift) * scale / direction_length).collect()
+    }
+
+    pub fn normal_at(&self, point: Vec3) -> Vec3 {
+        // Gradient de (|p|² + R² - r²)² - 4R²(x² + z²)
+        let p = point - self.center;
+        let k = p.dot(p) + self.radius * self.radius - self.tube * self.tube;
+        let r2 = 2.0 * self.radius * self.radius;
+        Vec3::new(p.x * (k - r2), p.y * k, p.z * (k - r2)).normalize()
+    }
+}
+
+impl Renderable for Torus {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let t = self.roots(ray).into_iter().find(|&t| t > 1e-9)?;
+        let point = ray.at(t);
+        Some(Intersection { point, normal: self.normal_at(point), distance: t, material: self.material })
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = Vec3::new(self.radius + self.tube, self.tube, self.radius + self.tube);
+        Aabb::new(self.center - extent, self.center + extent)
+    }
+}
+
+// Capsule d'axe vertical (+Y) : un cylindre fermé par deux demi-sphères.
+// La position est le point le plus bas, `height` la hauteur totale.
+#[derive(Debug)]
+pub struct Capsule {
+    pub center: Vec3,
+    pub radius: f64,
+    pub height: f64,
+    pub material: Material,
+}
+
+impl Capsule {
+    //radius par défaut 5, height par défaut 20 (au moins 2 * radius)
+    pub fn new(shape: Shape) -> Result<Self, String> {
+        shape.params.check(&["radius", "height"], &shape.shape_type)?;
+        let center = shape.location;
+        let radius = shape.params.positive("radius", 5.0)?;
+        let height = shape.params.positive("height", 20.0)?;
+        if height < 2.0 * radius || !height.is_finite() {
+            return Err(format!("Parameter 'height' must be finite and at least twice the radius ({})", 2.0 * radius));
+        }
+
+        Ok(Capsule { center, radius, height, material: shape.material })
+    }
+
+    // Centres des demi-sphères du bas et du haut
+    fn ends(&self) -> (Vec3, Vec3) {
+        (
+            self.center + Vec3::new(0.0, self.radius, 0.0),
+            self.center + Vec3::new(0.0, self.height - self.radius, 0.0),
+        )
+    }
+
+    // Distances où la droite du rayon traverse la surface (y compris derrière l'origine), triées
+    pub fn roots(&self, ray: &Ray) -> Vec<f64> {
+        let (bottom, top) = self.ends();
+        let d = ray.direction;
+        let r2 = self.radius * self.radius;
+
+        // Solutions de la partie cylindrique, puis des deux sphères ; chacune n'est gardée que dans sa zone
+        let oc = ray.origin - bottom;
+        let mut roots = solve_quadratic(d.x * d.x + d.z * d.z, 2.0 * (oc.x * d.x + oc.z * d.z), oc.x * oc.x + oc.z * oc.z - r2);
+        roots.retain(|&t| (bottom.y..=top.y).contains(&ray.at(t).y));
+        for (end, below) in [(bottom, true), (top, false)] {
+            let oc = ray.origin - end;
+            let sphere_roots = solve_quadratic(d.dot(d), 2.0 * oc.dot(d), oc.dot(oc) - r2);
+            roots.extend(sphere_roots.into_iter().filter(|&t| (ray.at(t).y < end.y) == below));
+        }
+        roots.sort_by(f64::total_cmp);
+        roots
+    }
+
+    pub fn normal_at(&self, point: Vec3) -> Vec3 {
+        let (bottom, top) = self.ends();
+        let on_axis = Vec3::new(self.center.x, point.y.clamp(bottom.y, top.y), self.center.z);
+        (point - on_axis).normalize()
+    }
+}
+
+impl Renderable for Capsule {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let t = self.roots(ray).into_iter().find(|&t| t > 0.0)?;
+        let point = ray.at(t);
+        Some(Intersection { point, normal: self.normal_at(point), distance: t, material: self.material })
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(self.center - Vec3::new(self.radius, 0.0, self.radius), self.center + Vec3::new(self.radius, self.height, self.radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::utils::Params;
+
+    fn shape(shape_type: &str, params: &[&str]) -> Shape {
+        Shape {
+            shape_type: shape_type.to_string(),
+            material: Material::default(),
+            location: Vec3::new(0.0, 0.0, 0.0),
+            directory: PathBuf::new(),
+            params: Params::parse(params).unwrap(),
+        }
+    }
+
+    fn cone(params: &[&str]) -> Cone {
+        Cone::new(shape("cone", params)).unwrap()
+    }
+
+    fn capsule(params: &[&str]) -> Capsule {
+        Capsule::new(shape("capsule", params)).unwrap()
+    }
+
+    fn hit(object: &dyn Renderable, origin: Vec3, direction: Vec3) -> Option<Intersection> {
+        object.intersect(&Ray::new(origin, direction.normalize()))
+    }
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-6, "expected {:?}, found {:?}", b, a);
+    }
+
+    fn torus(center: Vec3) -> Torus {
+        Torus { center, radius: 10.0, tube: 3.0, material: Material::default() }
+    }
+
+    fn assert_roots(found: Vec<f64>, expected: &[f64]) {
+        assert_eq!(found.len(), expected.len(), "expected {:?}, found {:?}", expected, found);
+        for (root, expected_root) in found.iter().zip(expected) {
+            assert!((root - expected_root).abs() < 1e-6, "expected {:?}, found {:?}", expected, found);
+        }
+    }
+
+    #[test]
+    fn torus_roots_along_a_diameter() {
+        // Le rayon traverse le tube à |x| = 7 et |x| = 13 de part et d'autre du centre
+        let center = Vec3::new(2.0, 1.0, -4.0);
+        let ray = Ray::new(center + Vec3::new(-20.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert_roots(torus(center).roots(&ray), &[7.0, 13.0, 27.0, 33.0]);
+    }
+
+    #[test]
+    fn torus_roots_scale_with_the_direction_length() {
+        let ray = Ray::new(Vec3::new(-20.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0));
+        assert_roots(torus(Vec3::new(0.0, 0.0, 0.0)).roots(&ray), &[3.5, 6.5, 13.5, 16.5]);
+    }
+
+    #[test]
+    fn torus_roots_across_the_tube() {
+        // Rayon vertical au centre du tube : il entre à y = 3 et sort à y = -3
+        let ray = Ray::new(Vec3::new(10.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert_roots(torus(Vec3::new(0.0, 0.0, 0.0)).roots(&ray), &[7.0, 13.0]);
+    }
+
+    #[test]
+    fn ray_through_the_hole_misses_the_torus() {
+        let torus = torus(Vec3::new(0.0, 0.0, 0.0));
+        let ray = Ray::new(Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert_roots(torus.roots(&ray), &[]);
+        assert!(torus.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn torus_hit_from_inside_the_tube() {
+        // Depuis le centre du tube, le rayon sort à 3 unités
+        let intersection = torus(Vec3::new(0.0, 0.0, 0.0)).intersect(&Ray::new(Vec3::new(10.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0))).unwrap();
+        assert!((intersection.distance - 3.0).abs() < 1e-6);
+        assert!((intersection.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn cone_side_hit_and_normal() {
+        // Rayon 10 à la base, pointe à y = 20 : à mi-hauteur le rayon du cône vaut 5
+        let intersection = hit(&cone(&[]), Vec3::new(-20.0, 10.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        assert!((intersection.distance - 15.0).abs() < 1e-6);
+        assert_close(intersection.point, Vec3::new(-5.0, 10.0, 0.0));
+        assert_close(intersection.normal, Vec3::new(-2.0, 1.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn ray_above_the_apex_misses_the_cone() {
+        // L'équation du cône a aussi des solutions sur le cône opposé, au-delà de la pointe
+        let cone = cone(&[]);
+        let ray = Ray::new(Vec3::new(-20.0, 30.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(cone.side_roots(&ray).is_empty());
+        assert!(cone.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn capped_cone_hit_on_its_base() {
+        let intersection = hit(&cone(&[]), Vec3::new(3.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+        assert!((intersection.distance - 5.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn open_cone_is_hit_from_inside() {
+        // Sans disque, le rayon entre par la base et touche la paroi là où le rayon du cône vaut 3
+        let intersection = hit(&cone(&["caps=open"]), Vec3::new(3.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+        assert!((intersection.distance - 19.0).abs() < 1e-6);
+        assert_close(intersection.point, Vec3::new(3.0, 14.0, 0.0));
+        assert_close(intersection.normal, Vec3::new(2.0, 1.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn truncated_cone_hits() {
+        let cone = cone(&["top_radius=5", "height=10"]);
+
+        // Disque du haut
+        let intersection = hit(&cone, Vec3::new(2.0, 20.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((intersection.distance - 10.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(0.0, 1.0, 0.0));
+
+        // Paroi, à mi-hauteur où le rayon vaut 7.5
+        let intersection = hit(&cone, Vec3::new(0.0, 5.0, 20.0), Vec3::new(0.0, 0.0, -1.0)).unwrap();
+        assert!((intersection.distance - 12.5).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(0.0, 1.0, 2.0).normalize());
+
+        // Au-dessus du disque du haut, un rayon horizontal ne touche rien
+        assert!(hit(&cone, Vec3::new(-20.0, 12.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn capsule_roots_on_the_cylinder_and_the_ends() {
+        // Rayon 5, hauteur 20 : demi-sphères centrées à y = 5 et y = 15
+        let capsule = capsule(&[]);
+        assert_roots(capsule.roots(&Ray::new(Vec3::new(-20.0, 10.0, 0.0), Vec3::new(1.0, 0.0, 0.0))), &[15.0, 25.0]);
+        assert_roots(capsule.roots(&Ray::new(Vec3::new(0.0, 30.0, 0.0), Vec3::new(0.0, -1.0, 0.0))), &[10.0, 30.0]);
+    }
+
+    #[test]
+    fn capsule_normals() {
+        let capsule = capsule(&[]);
+
+        let intersection = hit(&capsule, Vec3::new(-20.0, 10.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        assert_close(intersection.normal, Vec3::new(-1.0, 0.0, 0.0));
+
+        // Sur la demi-sphère du haut, la normale part de son centre
+        let intersection = hit(&capsule, Vec3::new(3.0, 30.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((intersection.distance - 11.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(3.0, 4.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn capsule_hit_from_inside() {
+        let intersection = hit(&capsule(&[]), Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, 0.0, 1.0)).unwrap();
+        assert!((intersection.distance - 5.0).abs() < 1e-6);
+        assert_close(intersection.normal, Vec3::new(0.0, 0.0, 1.0));
+    }
+}
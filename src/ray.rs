@@ -1,7 +1,7 @@
 //Module pour la gestion des rayons
 
 use crate::vec3::Vec3;
-use crate::color::Color;
+use crate::material::Material;
 
 pub struct Ray {
     pub origin: Vec3,
@@ -23,5 +23,5 @@ pub struct Intersection {
     pub point: Vec3,
     pub normal: Vec3,
     pub distance: f64,
-    pub color: Color,
+    pub material: Material,
 }
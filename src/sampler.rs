@@ -0,0 +1,91 @@
+//Module pour la génération de nombres aléatoires utilisés par l'échantillonnage
+
+// Générateur pseudo-aléatoire (SplitMix64), rapide et reproductible.
+// Chaque pixel a son propre générateur, initialisé à partir de sa position : le rendu ne dépend
+// donc pas de l'ordre dans lequel les threads calculent les pixels.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    state: u64,
+}
+
+impl Sampler {
+    pub fn new(seed: u64) -> Sampler {
+        Sampler { state: seed }
+    }
+
+    // Générateur propre au pixel (x, y)
+    pub fn for_pixel(x: u32, y: u32) -> Sampler {
+        let mut sampler = Sampler::new(((x as u64) << 32) | y as u64);
+        // Mélange de l'état initial pour décorréler les pixels voisins
+        sampler.next_u64();
+        sampler
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Nombre aléatoire uniforme dans [0, 1[
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    pub fn next_2d(&mut self) -> (f64, f64) {
+        (self.next_f64(), self.next_f64())
+    }
+
+    // Point aléatoire dans la case `index` d'une grille de `count` cases couvrant [0, 1[²
+    // (échantillonnage stratifié : les points sont bien répartis tout en restant aléatoires)
+    pub fn stratified_2d(&mut self, index: u32, count: u32) -> (f64, f64) {
+        let (columns, rows) = grid(count);
+        let (jx, jy) = self.next_2d();
+        (
+            ((index % columns) as f64 + jx) / columns as f64,
+            ((index / columns) as f64 + jy) / rows as f64,
+        )
+    }
+}
+
+// Dimensions (colonnes, lignes) d'une grille d'exactement `count` cases, la plus proche possible d'un carré :
+// une case laissée vide fausserait la moyenne des échantillons. Un nombre premier donne une seule ligne.
+pub fn grid(count: u32) -> (u32, u32) {
+    let count = count.max(1);
+    let rows = (1..=count.isqrt()).rev().find(|&rows| count.is_multiple_of(rows)).unwrap_or(1);
+    (count / rows, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_has_exactly_count_cells() {
+        assert_eq!(grid(1), (1, 1));
+        assert_eq!(grid(3), (3, 1));
+        assert_eq!(grid(5), (5, 1));
+        assert_eq!(grid(6), (3, 2));
+        assert_eq!(grid(16), (4, 4));
+    }
+
+    #[test]
+    fn every_stratum_gets_one_sample() {
+        let mut sampler = Sampler::new(7);
+        for count in 1..=20 {
+            let (columns, rows) = grid(count);
+            let mut cells: Vec<(u32, u32)> = (0..count)
+                .map(|index| {
+                    let (x, y) = sampler.stratified_2d(index, count);
+                    assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+                    ((x * columns as f64) as u32, (y * rows as f64) as u32)
+                })
+                .collect();
+            cells.sort();
+            cells.dedup();
+            assert_eq!(cells.len(), count as usize, "count {}", count);
+        }
+    }
+}
@@ -1,8 +1,16 @@
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use crate::color::*;
 use crate::objects::*;
-use crate::vec3::*;
 use crate::camera::*;
 use crate::utils::*;
+use crate::ray::*;
+use crate::bvh::Bvh;
+use crate::filter::PixelFilter;
+use crate::sampler::Sampler;
+use crate::light::Light;
+use crate::integrator::Integrator;
 
 //Module pour la gestion de la scène (gestion des objets et des lumières)
 
@@ -10,45 +18,75 @@ use crate::utils::*;
 pub struct SceneParams {
     pub image_size: (u32, u32),
     pub background_color: Color,
+    pub ambient_color: Color, // Lumière ambiante ajoutée à chaque surface, indépendamment des lumières
     pub camera: Camera,
+    pub samples_per_pixel: u32,     // Nombre de rayons lancés par pixel (anti-crénelage)
+    pub pixel_filter: PixelFilter,  // Filtre de reconstruction appliqué aux échantillons d'un pixel
+    pub integrator: Box<dyn Integrator>, // Méthode de calcul de la couleur des rayons (whitted par défaut)
     pub lights: Vec<Light>, 
     pub objects: Vec<Object>,
+    pub bvh: Bvh, // Construit une fois à partir de `objects`, après la lecture de la configuration
 }
 
 
-#[derive(Debug)]
-pub struct Light {
-    pub position: Vec3,
-    pub intensity: f64,
-    pub color: Color,
+// Erreur de chargement d'un fichier de configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneError {
+    pub path: String,   // Chemin du fichier de configuration
+    pub line: usize,    // Numéro de la ligne fautive (0 si l'erreur concerne tout le fichier)
+    pub text: String,   // Texte de la ligne fautive
+    pub reason: String, // Explication lisible de l'erreur
+}
+
+impl SceneError {
+    pub fn new(path: &str, line: usize, text: &str, reason: &str) -> SceneError {
+        SceneError {
+            path: path.to_string(),
+            line,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
 }
 
-impl Light {
-    // Calcule l'éclairement de la lumière à un point donné
-    pub fn light_at(&self, point: Vec3) -> (f64, Color) {
-        // Pour l'exemple, la lumière diminue avec la distance
-        let distance = (self.position - point).length();
-        let attenuation = 1.0 / (distance * distance);
-        let intensity = self.intensity * attenuation;
-        (intensity, self.color)
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path, self.reason)
+        } else {
+            write!(f, "{}:{}: {} (in '{}')", self.path, self.line, self.reason, self.text)
+        }
     }
 }
 
+impl std::error::Error for SceneError {}
+
+// Taille (en pixels) du côté des tuiles rendues par les threads
+const TILE_SIZE: u32 = 32;
+
+// Zone rectangulaire de l'image [x0, x1[ x [y0, y1[ rendue d'un bloc par un thread
+#[derive(Debug, Copy, Clone)]
+struct Tile {
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+}
+
 impl SceneParams {
 
-    pub fn render(&self, destination: &str){
-        let (width, height) = self.image_size;
-        let mut image = vec![vec![get_color("black"); width as usize]; height as usize];
+    // Intersection la plus proche du rayon avec les objets de la scène
+    pub fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        self.bvh.closest(ray, |index| self.objects[index].intersect(ray))
+    }
 
-        for y in 0..height {
-            for x in 0..width {
-                let u = x as f64 / width as f64;
-                let v = y as f64 / height as f64;
+    // Vrai si un objet coupe le rayon avant `max_distance` (utilisé pour les ombres)
+    pub fn occluded(&self, ray: &Ray, max_distance: f64) -> bool {
+        self.bvh.any(ray, max_distance, |index| self.objects[index].intersect(ray))
+    }
 
-                let ray = self.camera.get_ray(u, v);
-                image[y as usize][x as usize] = color(&ray, &self);
-            }
-        }
+    pub fn render(&self, destination: &str, threads: usize){
+        let image = self.render_image(threads);
 
         if destination.ends_with(".png") {
             // Sauvegarder l'image au format PNG
@@ -57,5 +95,96 @@ impl SceneParams {
             // Sauvegarder l'image au format PPM
             save_image(destination, &image);
         }
-    }       
-}
\ No newline at end of file
+    }
+
+    // Calcule l'image en découpant le rendu en tuiles réparties entre `threads` threads.
+    // Chaque pixel ne dépend que de sa position, le résultat est donc identique quel que soit le nombre de threads.
+    pub fn render_image(&self, threads: usize) -> Vec<Vec<Color>> {
+        let (width, height) = self.image_size;
+        let mut image = vec![vec![get_color("black"); width as usize]; height as usize];
+
+        let mut tiles = Vec::new();
+        for y0 in (0..height).step_by(TILE_SIZE as usize) {
+            for x0 in (0..width).step_by(TILE_SIZE as usize) {
+                tiles.push(Tile { x0, y0, x1: (x0 + TILE_SIZE).min(width), y1: (y0 + TILE_SIZE).min(height) });
+            }
+        }
+
+        // Les threads prennent la prochaine tuile libre jusqu'à ce qu'il n'en reste plus
+        let next_tile = AtomicUsize::new(0);
+        let threads = threads.clamp(1, tiles.len().max(1));
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads).map(|_| scope.spawn(|| {
+                let mut rendered = Vec::new();
+                loop {
+                    let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                    let Some(tile) = tiles.get(index) else { break };
+                    rendered.push((*tile, self.render_tile(tile)));
+                }
+                rendered
+            })).collect();
+
+            for worker in workers {
+                for (tile, pixels) in worker.join().expect("Render thread panicked") {
+                    let tile_width = (tile.x1 - tile.x0) as usize;
+                    for (row, line) in pixels.chunks(tile_width).enumerate() {
+                        let y = tile.y0 as usize + row;
+                        image[y][tile.x0 as usize..tile.x1 as usize].copy_from_slice(line);
+                    }
+                }
+            }
+        });
+
+        image
+    }
+
+    // Rend les pixels d'une tuile, ligne par ligne
+    fn render_tile(&self, tile: &Tile) -> Vec<Color> {
+        let mut pixels = Vec::with_capacity(((tile.x1 - tile.x0) * (tile.y1 - tile.y0)) as usize);
+        for y in tile.y0..tile.y1 {
+            for x in tile.x0..tile.x1 {
+                pixels.push(self.render_pixel(x, y));
+            }
+        }
+        pixels
+    }
+
+    // Couleur d'un pixel : moyenne pondérée par le filtre de `samples_per_pixel` échantillons stratifiés
+    fn render_pixel(&self, x: u32, y: u32) -> Color {
+        let (width, height) = self.image_size;
+        let mut sampler = Sampler::for_pixel(x, y);
+        let radius = self.pixel_filter.radius();
+
+        let mut sum = get_color("black");
+        let mut weight_sum = 0.0;
+
+        for sample in 0..self.samples_per_pixel {
+            // Décalage par rapport au centre du pixel, dans le support du filtre (un seul échantillon : le centre)
+            let (dx, dy) = if self.samples_per_pixel == 1 {
+                (0.0, 0.0)
+            } else {
+                let (sx, sy) = sampler.stratified_2d(sample, self.samples_per_pixel);
+                ((2.0 * sx - 1.0) * radius, (2.0 * sy - 1.0) * radius)
+            };
+            let weight = self.pixel_filter.weight(dx, dy);
+            if weight == 0.0 {
+                continue;
+            }
+
+            let u = (x as f64 + 0.5 + dx) / width as f64;
+            let v = (y as f64 + 0.5 + dy) / height as f64;
+
+            let ray = self.camera.get_ray(u, v);
+            sum = sum + self.integrator.li(&ray, self, &mut sampler) * weight;
+            weight_sum += weight;
+        }
+
+        if weight_sum.abs() < 1e-12 {
+            return get_color("black");
+        }
+        // Les lobes négatifs du filtre de Mitchell peuvent donner des composantes négatives
+        let pixel = sum.scale(1.0 / weight_sum);
+        Color::new(pixel.r.max(0.0), pixel.g.max(0.0), pixel.b.max(0.0))
+    }
+}
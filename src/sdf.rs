@@ -0,0 +1,389 @@
+//Module pour les objets définis par une fonction de distance signée (rendus par sphere tracing)
+
+use std::sync::Arc;
+use crate::vec3::*;
+use crate::ray::*;
+use crate::bvh::Aabb;
+use crate::material::Material;
+use crate::objects::*;
+use crate::utils::Params;
+
+// Nombre maximal de pas de la marche le long d'un rayon
+const MAX_STEPS: usize = 512;
+// Distance en dessous de laquelle le rayon est considéré sur la surface
+pub const HIT_EPSILON: f64 = 1e-4;
+// Longueur maximale parcourue, pour les formes répétées à l'infini
+pub const MAX_DISTANCE: f64 = 1e4;
+// Pas des différences centrées utilisées pour la normale
+const NORMAL_EPSILON: f64 = 1e-4;
+
+// Noeud de l'arbre d'une fonction de distance : une forme élémentaire, ou une opération sur d'autres noeuds
+#[derive(Debug)]
+pub enum SdfNode {
+    Sphere { center: Vec3, radius: f64 },
+    Box { center: Vec3, half_size: Vec3 },
+    RoundBox { center: Vec3, half_size: Vec3, radius: f64 }, // Boîte aux arêtes arrondies de `radius`
+    Torus { center: Vec3, radius: f64, tube: f64 },
+    SmoothUnion { left: Box<SdfNode>, right: Box<SdfNode>, k: f64 },       // k : largeur du raccord (0 : union nette)
+    SmoothSubtraction { left: Box<SdfNode>, right: Box<SdfNode>, k: f64 }, // Le premier noeud moins le second
+    Repeat { period: Vec3, child: Box<SdfNode> },   // Répétition infinie selon les axes de période non nulle
+    Twist { rate: f64, lipschitz: f64, child: Box<SdfNode> }, // Torsion autour de l'axe y, `rate` en radians par unité de hauteur
+}
+
+impl SdfNode {
+    // Distance signée du point p à la surface (négative à l'intérieur)
+    pub fn distance(&self, p: Vec3) -> f64 {
+        match self {
+            SdfNode::Sphere { center, radius } => (p - *center).length() - radius,
+            SdfNode::Box { center, half_size } => box_distance(p - *center, *half_size),
+            SdfNode::RoundBox { center, half_size, radius } => {
+                box_distance(p - *center, *half_size - Vec3::new(*radius, *radius, *radius)) - radius
+            }
+            SdfNode::Torus { center, radius, tube } => {
+                let q = p - *center;
+                let ring = (q.x * q.x + q.z * q.z).sqrt() - radius;
+                (ring * ring + q.y * q.y).sqrt() - tube
+            }
+            SdfNode::SmoothUnion { left, right, k } => {
+                let (a, b) = (left.distance(p), right.distance(p));
+                if *k <= 0.0 {
+                    return a.min(b);
+                }
+                // Minimum adouci polynomial : les deux surfaces se raccordent sur une largeur k
+                let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+                b + (a - b) * h - k * h * (1.0 - h)
+            }
+            SdfNode::SmoothSubtraction { left, right, k } => {
+                let (a, b) = (left.distance(p), right.distance(p));
+                if *k <= 0.0 {
+                    return a.max(-b);
+                }
+                let h = (0.5 - 0.5 * (a + b) / k).clamp(0.0, 1.0);
+                a + (-b - a) * h + k * h * (1.0 - h)
+            }
+            SdfNode::Repeat { period, child } => {
+                let wrap = |value: f64, period: f64| if period > 0.0 { value - period * (value / period).round() } else { value };
+                child.distance(Vec3::new(wrap(p.x, period.x), wrap(p.y, period.y), wrap(p.z, period.z)))
+            }
+            SdfNode::Twist { rate, lipschitz, child } => {
+                let (sin, cos) = (rate * p.y).sin_cos();
+                let q = Vec3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z);
+                // La torsion étire l'espace : la distance est divisée par sa constante de Lipschitz pour rester sûre
+                child.distance(q) / lipschitz
+            }
+        }
+    }
+
+    // Boîte englobante dans le repère de la forme (infinie selon les axes répétés)
+    pub fn bounding_box(&self) -> Aabb {
+        match self {
+            SdfNode::Sphere { center, radius } => {
+                let r = Vec3::new(*radius, *radius, *radius);
+                Aabb::new(*center - r, *center + r)
+            }
+            SdfNode::Box { center, half_size } | SdfNode::RoundBox { center, half_size, .. } => {
+                Aabb::new(*center - *half_size, *center + *half_size)
+            }
+            SdfNode::Torus { center, radius, tube } => {
+                let extent = Vec3::new(radius + tube, *tube, radius + tube);
+                Aabb::new(*center - extent, *center + extent)
+            }
+            SdfNode::SmoothUnion { left, right, k } => {
+                // Le raccord peut dépasser l'union des deux formes d'au plus k/4
+                let bounds = left.bounding_box().union(&right.bounding_box());
+                let margin = Vec3::new(k / 4.0, k / 4.0, k / 4.0);
+                Aabb { min: bounds.min - margin, max: bounds.max + margin }
+            }
+            SdfNode::SmoothSubtraction { left, .. } => left.bounding_box(),
+            SdfNode::Repeat { period, child } => {
+                let bounds = child.bounding_box();
+                let unbounded = |period: f64, value: f64, infinity: f64| if period > 0.0 { infinity } else { value };
+                Aabb {
+                    min: Vec3::new(
+                        unbounded(period.x, bounds.min.x, f64::NEG_INFINITY),
+                        unbounded(period.y, bounds.min.y, f64::NEG_INFINITY),
+                        unbounded(period.z, bounds.min.z, f64::NEG_INFINITY),
+                    ),
+                    max: Vec3::new(
+                        unbounded(period.x, bounds.max.x, f64::INFINITY),
+                        unbounded(period.y, bounds.max.y, f64::INFINITY),
+                        unbounded(period.z, bounds.max.z, f64::INFINITY),
+                    ),
+                }
+            }
+            SdfNode::Twist { child, .. } => {
+                let bounds = child.bounding_box();
+                let r = horizontal_radius(&bounds);
+                Aabb { min: Vec3::new(-r, bounds.min.y, -r), max: Vec3::new(r, bounds.max.y, r) }
+            }
+        }
+    }
+}
+
+fn box_distance(p: Vec3, half_size: Vec3) -> f64 {
+    let q = Vec3::new(p.x.abs() - half_size.x, p.y.abs() - half_size.y, p.z.abs() - half_size.z);
+    let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).length();
+    outside + q.x.max(q.y).max(q.z).min(0.0)
+}
+
+// Plus grande distance à l'axe y d'un point de la boîte
+fn horizontal_radius(bounds: &Aabb) -> f64 {
+    let x = bounds.min.x.abs().max(bounds.max.x.abs());
+    let z = bounds.min.z.abs().max(bounds.max.z.abs());
+    (x * x + z * z).sqrt()
+}
+
+// Lit un arbre de distance écrit en notation préfixe, une ligne par noeud : chaque opération
+// est suivie de ses enfants (deux pour smooth_union et smooth_subtraction, un pour repeat et twist).
+// `lines` contient les numéros et le texte des lignes ; les erreurs indiquent la ligne fautive.
+pub fn parse_sdf(lines: &[(usize, &str)], header: usize) -> Result<SdfNode, (usize, String)> {
+    let mut position = 0;
+    let root = parse_node(lines, &mut position, header)?;
+    if let Some(&(index, _)) = lines.get(position) {
+        return Err((index, "Unexpected node after the end of the tree (missing operation?)".to_string()));
+    }
+    Ok(root)
+}
+
+fn parse_node(lines: &[(usize, &str)], position: &mut usize, parent: usize) -> Result<SdfNode, (usize, String)> {
+    let Some(&(index, line)) = lines.get(*position) else {
+        return Err((parent, "Missing child node".to_string()));
+    };
+    *position += 1;
+
+    // Les erreurs des paramètres sont signalées sur la ligne du noeud, celles des enfants sur leur propre ligne
+    let at = |e: String| (index, e);
+    let parts: Vec<&str> = line.split('/').map(|part| part.trim()).collect();
+    let kind = parts[0];
+    let params = Params::parse(&parts[1..]).map_err(at)?;
+    let center = params.vec3("center", Vec3::new(0.0, 0.0, 0.0)).map_err(at)?;
+
+    match kind {
+        "sphere" => {
+            params.check(&["center", "radius"], kind).map_err(at)?;
+            Ok(SdfNode::Sphere { center, radius: params.positive("radius", 10.0).map_err(at)? })
+        }
+        "box" => {
+            params.check(&["center", "size"], kind).map_err(at)?;
+            Ok(SdfNode::Box { center, half_size: half_size(&params).map_err(at)? })
+        }
+        "round_box" => {
+            params.check(&["center", "size", "radius"], kind).map_err(at)?;
+            let half_size = half_size(&params).map_err(at)?;
+            let radius = params.positive("radius", 1.0).map_err(at)?;
+            if radius > half_size.x.min(half_size.y).min(half_size.z) {
+                return Err(at("Parameter 'radius' must not be greater than half the smallest side".to_string()));
+            }
+            Ok(SdfNode::RoundBox { center, half_size, radius })
+        }
+        "torus" => {
+            params.check(&["center", "radius", "tube"], kind).map_err(at)?;
+            let radius = params.positive("radius", 10.0).map_err(at)?;
+            let tube = params.positive("tube", 3.0).map_err(at)?;
+            Ok(SdfNode::Torus { center, radius, tube })
+        }
+        "smooth_union" | "smooth_subtraction" => {
+            params.check(&["k"], kind).map_err(at)?;
+            let k = params.number("k", 2.0).map_err(at)?;
+            if k < 0.0 {
+                return Err(at(format!("Parameter 'k' must not be negative, found {}", k)));
+            }
+            let left = Box::new(parse_node(lines, position, index)?);
+            let right = Box::new(parse_node(lines, position, index)?);
+            Ok(if kind == "smooth_union" { SdfNode::SmoothUnion { left, right, k } } else { SdfNode::SmoothSubtraction { left, right, k } })
+        }
+        "repeat" => {
+            params.check(&["period"], kind).map_err(at)?;
+            let period = params.vec3("period", Vec3::new(0.0, 0.0, 0.0)).map_err(at)?;
+            if period.x < 0.0 || period.y < 0.0 || period.z < 0.0 || period.length() == 0.0 {
+                return Err(at("Parameter 'period' must not be negative and must repeat along at least one axis".to_string()));
+            }
+            Ok(SdfNode::Repeat { period, child: Box::new(parse_node(lines, position, index)?) })
+        }
+        "twist" => {
+            params.check(&["angle"], kind).map_err(at)?;
+            let rate = params.number("angle", 5.0).map_err(at)?.to_radians();
+            let child = parse_node(lines, position, index)?;
+            let bounds = child.bounding_box();
+            if !bounds.is_finite() {
+                return Err(at("A twist needs a bounded child (no repeat inside a twist)".to_string()));
+            }
+            let r = horizontal_radius(&bounds);
+            let lipschitz = (1.0 + (rate * r) * (rate * r)).sqrt();
+            Ok(SdfNode::Twist { rate, lipschitz, child: Box::new(child) })
+        }
+        _ => Err(at(format!("Unknown distance node '{}'", kind))),
+    }
+}
+
+// Paramètre size (dimensions complètes, 10 par défaut), renvoyé sous forme de demi-dimensions
+fn half_size(params: &Params) -> Result<Vec3, String> {
+    let size = params.vec3("size", Vec3::new(10.0, 10.0, 10.0))?;
+    if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+        return Err("Parameter 'size' must be greater than 0 on every axis".to_string());
+    }
+    Ok(size / 2.0)
+}
+
+// Objet dont la surface est l'ensemble des points de distance nulle d'un arbre (partagé entre les formes qui l'utilisent)
+#[derive(Debug)]
+pub struct Sdf {
+    pub root: Arc<SdfNode>,
+    pub center: Vec3,
+    pub material: Material,
+    pub bounds: Aabb, // Boîte englobante dans le repère de l'arbre
+}
+
+impl Sdf {
+    pub fn new(root: Arc<SdfNode>, center: Vec3, material: Material) -> Sdf {
+        let bounds = root.bounding_box();
+        Sdf { root, center, material, bounds }
+    }
+
+    // Normale par différences centrées de la fonction de distance
+    pub fn normal_at(&self, point: Vec3) -> Vec3 {
+        let p = point - self.center;
+        let h = NORMAL_EPSILON;
+        let d = |offset: Vec3| self.root.distance(p + offset) - self.root.distance(p - offset);
+        Vec3::new(d(Vec3::new(h, 0.0, 0.0)), d(Vec3::new(0.0, h, 0.0)), d(Vec3::new(0.0, 0.0, h))).normalize()
+    }
+}
+
+impl Renderable for Sdf {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        // La marche se fait avec une direction unitaire : la distance parcourue est alors bornée par la fonction de distance
+        let length = ray.direction.length();
+        let direction = ray.direction / length;
+        let origin = ray.origin - self.center;
+
+        // Seule la partie du rayon dans la boîte englobante est parcourue
+        let (mut start, mut end) = (0.0_f64, MAX_DISTANCE);
+        for (o, d, min, max) in [
+            (origin.x, direction.x, self.bounds.min.x, self.bounds.max.x),
+            (origin.y, direction.y, self.bounds.min.y, self.bounds.max.y),
+            (origin.z, direction.z, self.bounds.min.z, self.bounds.max.z),
+        ] {
+            let (t1, t2) = ((min - o) / d, (max - o) / d);
+            start = start.max(t1.min(t2));
+            end = end.min(t1.max(t2));
+        }
+        if start > end {
+            return None;
+        }
+
+        // Un rayon qui part de l'intérieur (réfraction) cherche la sortie : la distance est alors prise au signe opposé
+        let sign = if self.root.distance(origin + direction * start) < 0.0 { -1.0 } else { 1.0 };
+        // Un rayon qui part de la surface (rayon secondaire) doit s'en éloigner avant de pouvoir la toucher à nouveau.
+        // Les autres peuvent la toucher dès l'entrée dans la boîte englobante, qui n'est agrandie que de la marge de l'Aabb.
+        let mut left_surface = self.root.distance(origin).abs() >= 2.0 * HIT_EPSILON;
+        let mut s = start;
+        for _ in 0..MAX_STEPS {
+            let distance = sign * self.root.distance(origin + direction * s);
+            if distance < HIT_EPSILON {
+                if left_surface {
+                    let t = s / length;
+                    let point = ray.at(t);
+                    return Some(Intersection { point, normal: self.normal_at(point), distance: t, material: self.material });
+                }
+            } else {
+                left_surface = true;
+            }
+            s += distance.max(HIT_EPSILON);
+            if s > end {
+                break;
+            }
+        }
+
+        None
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        if !self.bounds.is_finite() {
+            return Aabb::infinite();
+        }
+        Aabb { min: self.bounds.min + self.center, max: self.bounds.max + self.center }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sdf(root: SdfNode, center: Vec3) -> Sdf {
+        Sdf::new(Arc::new(root), center, Material::default())
+    }
+
+    fn sphere(center: Vec3, radius: f64) -> SdfNode {
+        SdfNode::Sphere { center, radius }
+    }
+
+    #[test]
+    fn sphere_hit_from_outside() {
+        let sdf = sdf(sphere(Vec3::new(0.0, 0.0, 0.0), 2.0), Vec3::new(1.0, 2.0, 3.0));
+        let intersection = sdf.intersect(&Ray::new(Vec3::new(-9.0, 2.0, 3.0), Vec3::new(2.0, 0.0, 0.0))).unwrap();
+        // La distance est mesurée en longueurs de la direction du rayon, comme pour les autres objets
+        assert!((intersection.distance - 4.0).abs() < 1e-3, "found {}", intersection.distance);
+        assert!((intersection.normal - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn ray_leaving_the_surface_does_not_hit_it_again() {
+        let sdf = sdf(sphere(Vec3::new(0.0, 0.0, 0.0), 2.0), Vec3::new(0.0, 0.0, 0.0));
+        let origin = Vec3::new(0.0, 2.0 + 1e-6, 0.0);
+        assert!(sdf.intersect(&Ray::new(origin, Vec3::new(1.0, 1.0, 0.0).normalize())).is_none());
+    }
+
+    #[test]
+    fn refracted_ray_exits_on_the_far_side() {
+        let sdf = sdf(sphere(Vec3::new(0.0, 0.0, 0.0), 2.0), Vec3::new(0.0, 0.0, 0.0));
+        let origin = Vec3::new(0.0, 2.0 - 1e-6, 0.0);
+        let intersection = sdf.intersect(&Ray::new(origin, Vec3::new(0.0, -1.0, 0.0))).unwrap();
+        assert!((intersection.distance - 4.0).abs() < 1e-3, "found {}", intersection.distance);
+    }
+
+    #[test]
+    fn ray_from_one_part_reaches_the_other() {
+        // Union de deux sphères séparées : un rayon qui quitte la première touche la seconde
+        let root = SdfNode::SmoothUnion {
+            left: Box::new(sphere(Vec3::new(-5.0, 0.0, 0.0), 1.0)),
+            right: Box::new(sphere(Vec3::new(5.0, 0.0, 0.0), 1.0)),
+            k: 0.0,
+        };
+        let sdf = sdf(root, Vec3::new(0.0, 0.0, 0.0));
+        let intersection = sdf.intersect(&Ray::new(Vec3::new(-4.0 + 1e-6, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0))).unwrap();
+        assert!((intersection.distance - 8.0).abs() < 1e-3, "found {}", intersection.distance);
+    }
+
+    #[test]
+    fn box_hit_from_outside_and_from_its_surface() {
+        let sdf = sdf(SdfNode::Box { center: Vec3::new(0.0, 0.0, 0.0), half_size: Vec3::new(1.0, 2.0, 3.0) }, Vec3::new(0.0, 0.0, 0.0));
+        let intersection = sdf.intersect(&Ray::new(Vec3::new(0.5, 10.0, 0.5), Vec3::new(0.0, -1.0, 0.0))).unwrap();
+        assert!((intersection.distance - 8.0).abs() < 1e-3, "found {}", intersection.distance);
+        assert!((intersection.normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-3);
+
+        // Reflet sur la face du dessus : le rayon s'éloigne de la boîte
+        assert!(sdf.intersect(&Ray::new(Vec3::new(0.5, 2.0 + 1e-6, 0.5), Vec3::new(1.0, 1.0, 0.0).normalize())).is_none());
+        // Rayon réfracté : il ressort par la face du dessous
+        let exit = sdf.intersect(&Ray::new(Vec3::new(0.5, 2.0 - 1e-6, 0.5), Vec3::new(0.0, -1.0, 0.0))).unwrap();
+        assert!((exit.distance - 4.0).abs() < 1e-3, "found {}", exit.distance);
+    }
+
+    #[test]
+    fn face_on_rays_hit_off_origin_box() {
+        // Les rayons entrent dans la boîte englobante à moins de HIT_EPSILON de la face
+        let center = Vec3::new(3.3, 7.1, -2.9);
+        let sdf = sdf(SdfNode::Box { center: Vec3::new(0.0, 0.0, 0.0), half_size: Vec3::new(7.7, 7.7, 7.7) }, center);
+        for i in 0..50 {
+            for j in 0..50 {
+                let (x, y) = (-7.5 + 15.0 * i as f64 / 49.0, -7.5 + 15.0 * j as f64 / 49.0);
+                let origin = center + Vec3::new(x, y, 30.0);
+                let intersection = sdf.intersect(&Ray::new(origin, Vec3::new(0.0, 0.0, -1.0))).expect("face-on ray missed the box");
+                assert!((intersection.distance - (30.0 - 7.7)).abs() < 1e-3, "found {}", intersection.distance);
+            }
+        }
+    }
+}
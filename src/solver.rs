@@ -0,0 +1,181 @@
+//Module pour la résolution des équations polynomiales (intersections avec les surfaces courbes)
+
+use std::f64::consts::PI;
+
+// Seuil en dessous duquel un coefficient est considéré comme nul
+const EPSILON: f64 = 1e-12;
+
+// Racines réelles de a·x² + b·x + c, triées par ordre croissant.
+// La forme q = -(b ± √Δ)/2 évite la perte de précision quand b² est grand devant 4ac.
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        if b.abs() < EPSILON {
+            return Vec::new();
+        }
+        return vec![-c / b];
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let mut roots = if q.abs() < EPSILON {
+        vec![-b / (2.0 * a)]
+    } else {
+        vec![q / a, c / q]
+    };
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+// Racines réelles de x³ + a·x² + b·x + c (méthode de Cardan, forme trigonométrique s'il y a trois racines)
+pub fn solve_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    // Changement de variable x = y - a/3 : y³ + p·y + 2q = 0
+    let a2 = a * a;
+    let p = (b - a2 / 3.0) / 3.0;
+    let q = (2.0 / 27.0 * a * a2 - a * b / 3.0 + c) / 2.0;
+    let p3 = p * p * p;
+    let discriminant = q * q + p3;
+
+    let mut roots = if discriminant.abs() < EPSILON {
+        if q.abs() < EPSILON {
+            vec![0.0]
+        } else {
+            // Une racine simple et une racine double
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        // Trois racines réelles distinctes
+        let phi = (-q / (-p3).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![t * phi.cos(), -t * (phi + PI / 3.0).cos(), -t * (phi - PI / 3.0).cos()]
+    } else {
+        let sqrt_discriminant = discriminant.sqrt();
+        vec![(sqrt_discriminant - q).cbrt() - (sqrt_discriminant + q).cbrt()]
+    };
+
+    for root in roots.iter_mut() {
+        *root -= a / 3.0;
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+// Racines réelles de x⁴ + a·x³ + b·x² + c·x + d (méthode de Ferrari).
+// Chaque racine est ensuite affinée par quelques itérations de Newton sur le polynôme d'origine,
+// ce qui corrige les erreurs d'arrondi de la résolvante.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    // Changement de variable x = y - a/4 : y⁴ + p·y² + q·y + r = 0
+    let a2 = a * a;
+    let p = -3.0 / 8.0 * a2 + b;
+    let q = a2 * a / 8.0 - a * b / 2.0 + c;
+    let r = -3.0 / 256.0 * a2 * a2 + a2 * b / 16.0 - a * c / 4.0 + d;
+
+    let mut roots = if r.abs() < EPSILON {
+        // y·(y³ + p·y + q) = 0
+        let mut roots = solve_cubic(0.0, p, q);
+        roots.push(0.0);
+        roots
+    } else {
+        // Résolvante cubique ; sa plus grande racine donne la décomposition la plus stable
+        let resolvent = solve_cubic(-p / 2.0, -r, r * p / 2.0 - q * q / 8.0);
+        let z = match resolvent.last() {
+            Some(&z) => z,
+            None => return Vec::new(),
+        };
+
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        if u < -EPSILON || v < -EPSILON {
+            return Vec::new();
+        }
+        let u = u.max(0.0).sqrt();
+        let v = if q < 0.0 { -v.max(0.0).sqrt() } else { v.max(0.0).sqrt() };
+
+        let mut roots = solve_quadratic(1.0, v, z - u);
+        roots.extend(solve_quadratic(1.0, -v, z + u));
+        roots
+    };
+
+    for root in roots.iter_mut() {
+        *root -= a / 4.0;
+        *root = polish_quartic(*root, a, b, c, d);
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+fn polish_quartic(mut x: f64, a: f64, b: f64, c: f64, d: f64) -> f64 {
+    for _ in 0..3 {
+        let value = (((x + a) * x + b) * x + c) * x + d;
+        let derivative = ((4.0 * x + 3.0 * a) * x + 2.0 * b) * x + c;
+        if derivative.abs() < EPSILON {
+            break;
+        }
+        x -= value / derivative;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(found: Vec<f64>, expected: &[f64]) {
+        assert_eq!(found.len(), expected.len(), "expected {:?}, found {:?}", expected, found);
+        for (root, expected_root) in found.iter().zip(expected) {
+            assert!((root - expected_root).abs() < 1e-9, "expected {:?}, found {:?}", expected, found);
+        }
+    }
+
+    #[test]
+    fn quadratic_roots_are_sorted() {
+        // 2(x + 3)(x - 0.5)
+        assert_roots(solve_quadratic(2.0, 5.0, -3.0), &[-3.0, 0.5]);
+        assert_roots(solve_quadratic(1.0, 0.0, 1.0), &[]);
+        // Équation du premier degré
+        assert_roots(solve_quadratic(0.0, 2.0, -4.0), &[2.0]);
+    }
+
+    #[test]
+    fn quadratic_keeps_precision_when_b_is_large() {
+        // Racines 1e-8 et 1e8 : la forme naïve perd la petite racine
+        let roots = solve_quadratic(1.0, -(1e8 + 1e-8), 1.0);
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] / 1e-8 - 1.0).abs() < 1e-9, "found {:?}", roots);
+        assert!((roots[1] / 1e8 - 1.0).abs() < 1e-9, "found {:?}", roots);
+    }
+
+    #[test]
+    fn cubic_with_three_real_roots() {
+        // (x - 1)(x - 2)(x - 3)
+        assert_roots(solve_cubic(-6.0, 11.0, -6.0), &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn cubic_with_one_real_root() {
+        // (x - 2)(x² + 1)
+        assert_roots(solve_cubic(-2.0, 1.0, -2.0), &[2.0]);
+    }
+
+    #[test]
+    fn quartic_with_four_real_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(solve_quartic(-10.0, 35.0, -50.0, 24.0), &[1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn quartic_with_two_real_roots() {
+        // (x + 2)(x - 1)(x² + 1)
+        assert_roots(solve_quartic(1.0, -1.0, 1.0, -2.0), &[-2.0, 1.0]);
+    }
+
+    #[test]
+    fn quartic_without_real_roots() {
+        // (x² + 1)(x² + 4)
+        assert_roots(solve_quartic(0.0, 5.0, 0.0, 4.0), &[]);
+    }
+}
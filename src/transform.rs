@@ -0,0 +1,173 @@
+//Module pour les transformations affines (rotation, mise à l'échelle, translation)
+
+use std::ops::Mul;
+use crate::vec3::*;
+use crate::ray::Ray;
+use crate::bvh::Aabb;
+use crate::utils::Params;
+
+// Paramètres d'une forme qui décrivent sa transformation
+pub const TRANSFORM_PARAMS: [&str; 3] = ["rotation", "scale", "translate"];
+
+// Transformation affine 4x4, stockée avec son inverse pour ramener les rayons dans l'espace de l'objet
+#[derive(Debug, Copy, Clone)]
+pub struct Transform {
+    pub matrix: [[f64; 4]; 4],
+    pub inverse: [[f64; 4]; 4],
+}
+
+const IDENTITY: [[f64; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+fn multiply(a: &[[f64; 4]; 4], b: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut result = [[0.0; 4]; 4];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform { matrix: IDENTITY, inverse: IDENTITY }
+    }
+
+    pub fn translation(offset: Vec3) -> Transform {
+        let mut matrix = IDENTITY;
+        let mut inverse = IDENTITY;
+        for (axis, value) in [offset.x, offset.y, offset.z].into_iter().enumerate() {
+            matrix[axis][3] = value;
+            inverse[axis][3] = -value;
+        }
+        Transform { matrix, inverse }
+    }
+
+    // Mise à l'échelle selon chaque axe ; les facteurs doivent être non nuls
+    pub fn scaling(factors: Vec3) -> Transform {
+        let mut matrix = IDENTITY;
+        let mut inverse = IDENTITY;
+        for (axis, value) in [factors.x, factors.y, factors.z].into_iter().enumerate() {
+            matrix[axis][axis] = value;
+            inverse[axis][axis] = 1.0 / value;
+        }
+        Transform { matrix, inverse }
+    }
+
+    // Rotation autour de l'axe `axis` (0 : x, 1 : y, 2 : z), angle en degrés
+    pub fn rotation(axis: usize, degrees: f64) -> Transform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (i, j) = ((axis + 1) % 3, (axis + 2) % 3);
+        let mut matrix = IDENTITY;
+        matrix[i][i] = cos;
+        matrix[i][j] = -sin;
+        matrix[j][i] = sin;
+        matrix[j][j] = cos;
+        // L'inverse d'une rotation est sa transposée
+        let mut inverse = matrix;
+        inverse[i][j] = sin;
+        inverse[j][i] = -sin;
+        Transform { matrix, inverse }
+    }
+
+    // Rotation autour de x, puis de y, puis de z (angles en degrés)
+    pub fn rotation_xyz(degrees: Vec3) -> Transform {
+        Transform::rotation(2, degrees.z) * Transform::rotation(1, degrees.y) * Transform::rotation(0, degrees.x)
+    }
+
+    // Lit les paramètres rotation (degrés autour de x, y puis z), scale (nombre ou (x,y,z)) et translate.
+    // La mise à l'échelle est appliquée en premier, puis la rotation, puis la translation.
+    // Renvoie None si aucun de ces paramètres n'est donné.
+    pub fn from_params(params: &Params) -> Result<Option<Transform>, String> {
+        if TRANSFORM_PARAMS.iter().all(|key| params.get(key).is_none()) {
+            return Ok(None);
+        }
+
+        let scale = match params.get("scale") {
+            Some(value) if value.starts_with('(') => parse_vec3(value).map_err(|e| format!("Invalid value for parameter 'scale': {}", e))?,
+            _ => {
+                let s = params.positive("scale", 1.0)?;
+                Vec3::new(s, s, s)
+            }
+        };
+        if scale.x <= 0.0 || scale.y <= 0.0 || scale.z <= 0.0 {
+            return Err("Parameter 'scale' must be greater than 0".to_string());
+        }
+        let rotation = params.vec3("rotation", Vec3::new(0.0, 0.0, 0.0))?;
+        let translate = params.vec3("translate", Vec3::new(0.0, 0.0, 0.0))?;
+
+        Ok(Some(Transform::translation(translate) * Transform::rotation_xyz(rotation) * Transform::scaling(scale)))
+    }
+
+    // Même transformation, appliquée autour du point `pivot` au lieu de l'origine
+    pub fn around(&self, pivot: Vec3) -> Transform {
+        Transform::translation(pivot) * *self * Transform::translation(-pivot)
+    }
+
+    pub fn point(&self, p: Vec3) -> Vec3 {
+        apply(&self.matrix, p, 1.0)
+    }
+
+    pub fn vector(&self, v: Vec3) -> Vec3 {
+        apply(&self.matrix, v, 0.0)
+    }
+
+    // Les normales se transforment par la transposée de l'inverse, pour rester perpendiculaires à la surface
+    pub fn normal(&self, n: Vec3) -> Vec3 {
+        let m = &self.inverse;
+        Vec3::new(
+            m[0][0] * n.x + m[1][0] * n.y + m[2][0] * n.z,
+            m[0][1] * n.x + m[1][1] * n.y + m[2][1] * n.z,
+            m[0][2] * n.x + m[1][2] * n.y + m[2][2] * n.z,
+        ).normalize()
+    }
+
+    // Rayon exprimé dans l'espace de l'objet. La direction n'est pas renormalisée :
+    // la distance t d'une intersection est donc la même dans les deux espaces.
+    pub fn inverse_ray(&self, ray: &Ray) -> Ray {
+        Ray::new(apply(&self.inverse, ray.origin, 1.0), apply(&self.inverse, ray.direction, 0.0))
+    }
+
+    // Boîte englobant les huit coins transformés de `bounds`
+    pub fn bounding_box(&self, bounds: &Aabb) -> Aabb {
+        if !bounds.is_finite() {
+            return Aabb::infinite();
+        }
+        let mut result = Aabb::empty();
+        for corner in 0..8 {
+            let p = Vec3::new(
+                if corner & 1 == 0 { bounds.min.x } else { bounds.max.x },
+                if corner & 2 == 0 { bounds.min.y } else { bounds.max.y },
+                if corner & 4 == 0 { bounds.min.z } else { bounds.max.z },
+            );
+            let p = self.point(p);
+            result = result.union(&Aabb { min: p, max: p });
+        }
+        result
+    }
+}
+
+fn apply(m: &[[f64; 4]; 4], v: Vec3, w: f64) -> Vec3 {
+    Vec3::new(
+        m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z + m[0][3] * w,
+        m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z + m[1][3] * w,
+        m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z + m[2][3] * w,
+    )
+}
+
+// Composition : (a * b) applique b, puis a
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, other: Transform) -> Transform {
+        Transform {
+            matrix: multiply(&self.matrix, &other.matrix),
+            inverse: multiply(&other.inverse, &self.inverse),
+        }
+    }
+}
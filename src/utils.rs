@@ -1,164 +1,617 @@
 //Module utilitaire pour des fonctions diverses
+use std::collections::HashMap;
 use std::io::Write;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
+use std::sync::Arc;
 use std::f64::consts::PI;
 use crate::camera::Camera;
 use crate::color::*;
 use crate::vec3::*;
 use crate::objects::*;
 use crate::scene::*;
+use crate::bvh::Bvh;
+use crate::material::Material;
+use crate::filter::PixelFilter;
+use crate::group::{self, Group};
+use crate::csg::{Csg, CsgOperation};
+use crate::sdf::{self, Sdf, SdfNode};
+use crate::emitter::Emitter;
+use crate::transform::*;
+use crate::light::Light;
+use crate::integrator::{self, Integrator, Whitted};
 
 
 
-pub fn parse_config_file(file_path: &str) -> SceneParams {
+// Lit un fichier de configuration. En cas d'erreur, renvoie toutes les erreurs trouvées, dans l'ordre des lignes.
+pub fn parse_config_file(file_path: &str) -> Result<SceneParams, Vec<SceneError>> {
     let path = Path::new(file_path);
-    let file = File::open(&path).expect("Could not open file");
+    let file = File::open(path)
+        .map_err(|e| vec![SceneError::new(file_path, 0, "", &format!("Could not open file: {}", e))])?;
     let reader = io::BufReader::new(file);
-    let mut lines = reader.lines();
+
+    // Toutes les lignes sont lues d'abord, pour pouvoir signaler les erreurs avec leur numéro de ligne
+    let mut lines = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| vec![SceneError::new(file_path, index + 1, "", &format!("Could not read line: {}", e))])?;
+        lines.push(line);
+    }
+
+    // Construit une erreur pour la ligne d'indice `index` (les numéros de ligne commencent à 1)
+    let error_at = |index: usize, reason: &str| SceneError::new(file_path, index + 1, lines[index].trim(), reason);
 
     let mut image_size = (0, 0);
+    let mut image_size_given = false; // Vrai même si la valeur est invalide, pour ne pas signaler aussi son absence
     let mut background_color = get_color("white");
     let mut camera_position = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
     let mut camera_look_at = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
     let mut camera_up = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
     let mut camera_fov = 0.0;
     let mut camera_aspect_ratio = 0.0;
+    let mut samples_per_pixel = 1;
+    let mut pixel_filter = PixelFilter::Box;
+    let mut integrator: Box<dyn Integrator> = Box::new(Whitted);
     let mut light_position = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
     let mut light_intensity = 1.0;
     let mut light_color = get_color("white");
-    let mut objects = Vec::new();
-    let mut reading_shapes = false;
+    let mut legacy_light_line = None; // Ligne d'une clé light_* (ancienne syntaxe à une seule lumière)
+    let mut lights = None;            // Lumières de la section lights, si elle est présente
+    let mut ambient_color = None;
+    let mut materials = HashMap::new();
+    let mut shape_lines = Vec::new();
+    let mut group_definitions: Vec<GroupDefinition> = Vec::new();
+    let mut sdf_definitions: Vec<GroupDefinition> = Vec::new();
+    let mut section = Section::None;
 
-    while let Some(Ok(line)) = lines.next()  {
-        
-        if reading_shapes {
-            if line.contains("$$$ end_shape") {
-                break;
-            }
-            let shape_parts: Vec<&str> = line.split('/').collect();
+    // Lit la ligne d'indice i et renvoie le nombre de lignes consommées (une clé "$$$ ..." et sa valeur en font deux).
+    // Après une erreur, la lecture reprend à la ligne suivante, pour signaler toutes les erreurs du fichier.
+    let mut errors = Vec::new();
+    let mut parse_line = |i: usize, errors: &mut Vec<SceneError>| -> Result<usize, SceneError> {
+        let line = lines[i].trim();
 
-            if let Ok(object) =Object::new(Shape { shape_type: shape_parts[0].to_string(), color: shape_parts[1].to_string(), location: parse_vec3(shape_parts[2]) }) {
-                objects.push(object)
-            }else{
-                println!("Invalid Shape");
-                break;
+        if section != Section::None {
+            if line.contains(section.end_marker()) {
+                section = Section::None;
+            } else if line.starts_with("$$$") {
+                // Section sans marqueur de fin : elle est refermée, et la clé est lue normalement
+                errors.push(error_at(i, "Unexpected key inside a section (missing end marker?)"));
+                section = Section::None;
+                return Ok(0);
+            } else if !line.is_empty() && !line.starts_with('#') {
+                match section {
+                    // Les formes sont lues à la fin, une fois tous les matériaux connus
+                    Section::Shapes => shape_lines.push(i),
+                    Section::Group(index) | Section::Csg(index) => group_definitions[index].lines.push(i),
+                    Section::Sdf(index) => sdf_definitions[index].lines.push(i),
+                    Section::Materials => {
+                        let (name, material) = parse_material(line).map_err(|e| error_at(i, &format!("Invalid material: {}", e)))?;
+                        if materials.insert(name.clone(), material).is_some() {
+                            return Err(error_at(i, &format!("Material '{}' is defined more than once", name)));
+                        }
+                    }
+                    Section::Lights => {
+                        let light = parse_light(line).map_err(|e| error_at(i, &format!("Invalid light: {}", e)))?;
+                        lights.get_or_insert_with(Vec::new).push(light);
+                    }
+                    Section::None => {}
+                }
             }
-            
+            return Ok(1);
         }
 
-        if line.contains("$$$ shapes"){
-            reading_shapes = true;
-            continue;
+        if line.contains("$$$ shapes") {
+            section = Section::Shapes;
+            return Ok(1);
         }
 
-        if line.contains("$$$ image_size") {
-            if let Some(Ok(next_line)) = lines.next(){
-                let image_size_parts : Vec<&str> = next_line.split_whitespace().collect();
-                image_size = (
-                    image_size_parts[0].parse::<u32>().expect("Failed to parse width"),
-                    image_size_parts[1].parse::<u32>().expect("Failed to parse height")
-                );
+        // "$$$ group nom", "$$$ csg nom opération" ou "$$$ sdf nom" : définition nommée, utilisée par les lignes de formes
+        if line.contains("$$$ group") || line.contains("$$$ csg") || line.contains("$$$ sdf") {
+            let key = ["$$$ group", "$$$ csg", "$$$ sdf"].into_iter().find(|key| line.contains(key)).unwrap_or_default();
+            let mut words = line.trim_start_matches(key).split_whitespace();
+            let name = words.next().unwrap_or("");
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                return Err(error_at(i, "Expected a name made of letters, digits, '_' or '-' after the key"));
             }
+            if group_definitions.iter().chain(&sdf_definitions).any(|definition| definition.name == name) {
+                return Err(error_at(i, &format!("Name '{}' is defined more than once", name)));
+            }
+            let operation = if key == "$$$ csg" {
+                let operation = words.next().ok_or_else(|| error_at(i, "Expected '$$$ csg name union|intersection|difference'"))?;
+                Some(CsgOperation::from_name(operation).map_err(|e| error_at(i, &e))?)
+            } else {
+                None
+            };
+
+            let definition = GroupDefinition { name: name.to_string(), header: i, operation, lines: Vec::new() };
+            section = match key {
+                "$$$ sdf" => {
+                    sdf_definitions.push(definition);
+                    Section::Sdf(sdf_definitions.len() - 1)
+                }
+                "$$$ csg" => {
+                    group_definitions.push(definition);
+                    Section::Csg(group_definitions.len() - 1)
+                }
+                _ => {
+                    group_definitions.push(definition);
+                    Section::Group(group_definitions.len() - 1)
+                }
+            };
+            return Ok(1);
         }
 
-        if line.contains("$$$ background_color") {
-            if let Some(Ok(next_line)) = lines.next(){
-                background_color = get_color(&next_line);
-            }
+        if line.contains("$$$ materials") {
+            section = Section::Materials;
+            return Ok(1);
         }
 
-        if line.contains("$$$ light_position") {
-            if let Some(Ok(next_line)) = lines.next(){
-                if next_line == "default" {
-                    light_position =  Vec3 { x: 0.0, y: 50.0, z: 0.0 };
-                }else{
-                    light_position = parse_vec3(&next_line)
-                }
+        if line.contains("$$$ lights") {
+            if lights.is_some() {
+                return Err(error_at(i, "The lights section is given more than once"));
             }
+            lights = Some(Vec::new());
+            section = Section::Lights;
+            return Ok(1);
         }
 
-         if line.contains("$$$ light_intensity") {
-            if let Some(Ok(next_line)) = lines.next(){
-                if next_line == "low" {
-                    light_intensity =  0.7;
-                }else if next_line == "medium" {
-                    light_intensity =  1.0;
-                }else if next_line == "high" {
-                    light_intensity =  1.3;
-                }
+        // Les clés "$$$ ..." ont leur valeur sur la ligne suivante
+        if !line.starts_with("$$$") {
+            return Ok(1);
+        }
+        if i + 1 >= lines.len() {
+            return Err(error_at(i, "Missing value after this key"));
+        }
+        let value_index = i + 1;
+        let next_line = lines[value_index].trim();
+        let invalid_value = |reason: String| error_at(value_index, &reason);
+
+        if line.contains("$$$ image_size") {
+            image_size_given = true;
+            let image_size_parts : Vec<&str> = next_line.split_whitespace().collect();
+            if image_size_parts.len() != 2 {
+                return Err(invalid_value("Expected image size as 'width height'".to_string()));
             }
-         }
-        
-        if line.contains("$$$ light_color") {
-            if let Some(Ok(next_line)) = lines.next(){
-                light_color = get_color(&next_line);
-            }
-        }
-
-        if line.contains("$$$ camera_position") {
-            if let Some(Ok(next_line)) = lines.next(){
-                if next_line == "north" {
-                    camera_position = Vec3 {x:0.0, y:50.0, z:100.0};
-                }else if next_line == "west" {
-                    camera_position = Vec3 {x:100.0, y:50.0, z:0.0};
-                }else if next_line == "south" {
-                    camera_position = Vec3 {x:0.0, y:50.0, z:-100.0};
-                }else if next_line == "east" {
-                    camera_position = Vec3 {x:-100.0, y:50.0, z:0.0};
-                }else {
-                    camera_position = parse_vec3(&next_line)
-                }
+            let width = image_size_parts[0].parse::<u32>().map_err(|_| invalid_value(format!("Failed to parse width '{}'", image_size_parts[0])))?;
+            let height = image_size_parts[1].parse::<u32>().map_err(|_| invalid_value(format!("Failed to parse height '{}'", image_size_parts[1])))?;
+            if width == 0 || height == 0 {
+                return Err(invalid_value("Image width and height must be greater than 0".to_string()));
+            }
+            image_size = (width, height);
+        } else if line.contains("$$$ background_color") {
+            background_color = parse_color(next_line).map_err(invalid_value)?;
+        } else if line.contains("$$$ ambient_color") {
+            ambient_color = Some(parse_color(next_line).map_err(invalid_value)?);
+        } else if line.contains("$$$ light_position") {
+            legacy_light_line = Some(i);
+            if next_line == "default" {
+                light_position =  Vec3 { x: 0.0, y: 50.0, z: 0.0 };
+            }else{
+                light_position = parse_vec3(next_line).map_err(invalid_value)?;
+            }
+        } else if line.contains("$$$ light_intensity") {
+            legacy_light_line = Some(i);
+            light_intensity = match next_line {
+                "low" => 0.7,
+                "medium" => 1.0,
+                "high" => 1.3,
+                _ => return Err(invalid_value(format!("Unknown light intensity '{}', expected low, medium or high", next_line))),
+            };
+        } else if line.contains("$$$ light_color") {
+            legacy_light_line = Some(i);
+            light_color = parse_color(next_line).map_err(invalid_value)?;
+        } else if line.contains("$$$ camera_position") {
+            camera_position = match next_line {
+                "north" => Vec3 {x:0.0, y:50.0, z:100.0},
+                "west" => Vec3 {x:100.0, y:50.0, z:0.0},
+                "south" => Vec3 {x:0.0, y:50.0, z:-100.0},
+                "east" => Vec3 {x:-100.0, y:50.0, z:0.0},
+                _ => parse_vec3(next_line).map_err(invalid_value)?,
+            };
+        } else if line.contains("$$$ camera_look_at") {
+            if next_line == "default" {
+                camera_look_at = Vec3 {x:0.0, y:0.0, z:0.0};
+            }else {
+                camera_look_at = parse_vec3(next_line).map_err(invalid_value)?;
             }
+        } else if line.contains("$$$ camera_orientation") {
+            let degrees = next_line.parse::<f64>().map_err(|_| invalid_value("Failed to parse camera_orientation".to_string()))?;
+            let radians = degrees * PI / 180.0;
+            let y = radians.cos();
+            let x = radians.sin();
+            camera_up = Vec3 {x, y, z:0.0};
+        } else if line.contains("$$$ camera_fov") {
+            camera_fov = next_line.parse::<f64>().map_err(|_| invalid_value("Failed to parse camera_fov".to_string()))?;
+            if camera_fov <= 0.0 || camera_fov >= 180.0 {
+                return Err(invalid_value("camera_fov must be between 0 and 180 degrees".to_string()));
+            }
+        } else if line.contains("$$$ camera_aspect_ratio") {
+            camera_aspect_ratio = next_line.parse::<f64>().map_err(|_| invalid_value("Failed to parse camera_aspect_ratio".to_string()))?;
+            if camera_aspect_ratio <= 0.0 {
+                return Err(invalid_value("camera_aspect_ratio must be greater than 0".to_string()));
+            }
+        } else if line.contains("$$$ samples_per_pixel") {
+            samples_per_pixel = match next_line.parse::<u32>() {
+                Ok(n) if n > 0 => n,
+                _ => return Err(invalid_value("samples_per_pixel must be a whole number greater than 0".to_string())),
+            };
+        } else if line.contains("$$$ pixel_filter") {
+            pixel_filter = PixelFilter::from_name(next_line).map_err(invalid_value)?;
+        } else if line.contains("$$$ integrator") {
+            integrator = integrator::from_name(next_line).map_err(invalid_value)?;
+        } else {
+            return Err(error_at(i, "Unknown key"));
         }
 
-        if line.contains("$$$ camera_look_at") {
-            if let Some(Ok(next_line)) = lines.next(){
-                if next_line == "default" {
-                    camera_look_at = Vec3 {x:0.0, y:0.0, z:0.0};
-                }else {
-                    camera_look_at = parse_vec3(&next_line)
-                }
+        Ok(2)
+    };
+
+    let mut i = 0;
+    while i < lines.len() {
+        match parse_line(i, &mut errors) {
+            Ok(count) => i += count,
+            Err(error) => {
+                errors.push(error);
+                i += 1;
             }
         }
+    }
 
-        if line.contains("$$$ camera_orientation") {
-            if let Some(Ok(next_line)) = lines.next(){
-                let degrees = next_line.parse::<f64>().expect("Failed to parse camera_orientation");
-                let radians = degrees * PI / 180.0;
-                let y = radians.cos();
-                let x = radians.sin();
-                camera_up = Vec3 {x:x, y:y, z:0.0};
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut sdfs = HashMap::new();
+    for definition in &sdf_definitions {
+        let node_lines: Vec<(usize, &str)> = definition.lines.iter().map(|&index| (index, lines[index].trim())).collect();
+        match sdf::parse_sdf(&node_lines, definition.header) {
+            Ok(root) => {
+                sdfs.insert(definition.name.clone(), Arc::new(root));
             }
+            Err((index, e)) => errors.push(error_at(index, &format!("Invalid distance node: {}", e))),
         }
+    }
 
-        if line.contains("$$$ camera_fov") {
-            if let Some(Ok(next_line)) = lines.next(){
-                camera_fov = next_line.parse::<f64>().expect("Failed to parse camera_fov")
-            }
+    let mut groups = HashMap::new();
+    let context = GroupContext { definitions: &group_definitions, lines: &lines, materials: &materials, sdfs: &sdfs, directory, error_at: &error_at };
+    for index in 0..group_definitions.len() {
+        if let Err(error) = context.build(index, &mut groups, &mut Vec::new()) {
+            errors.push(error);
         }
+    }
 
-        if line.contains("$$$ camera_aspect_ratio") {
-            if let Some(Ok(next_line)) = lines.next(){
-                camera_aspect_ratio = next_line.parse::<f64>().expect("Failed to parse camera_aspect_ratio")
+    // Les objets de matériau émissif éclairent aussi la scène
+    let mut objects = Vec::new();
+    let mut emitters = Vec::new();
+    for index in shape_lines {
+        let object = match parse_shape(lines[index].trim(), &materials, &groups, &sdfs, directory) {
+            Ok(object) => object,
+            Err(e) => {
+                errors.push(error_at(index, &format!("Invalid shape: {}", e)));
+                continue;
             }
+        };
+        match Emitter::from_object(&object, objects.len()) {
+            Ok(emitter) => emitters.extend(emitter),
+            Err(e) => errors.push(error_at(index, &format!("Invalid shape: {}", e))),
         }
+        objects.push(object);
+    }
 
+    // Sans section lights, l'ancienne syntaxe décrit une seule lumière blanche ;
+    // light_color y donne alors la teinte de la lumière ambiante, comme auparavant
+    let mut lights = match (lights, legacy_light_line) {
+        (Some(lights), Some(index)) => {
+            errors.push(error_at(index, "light_* keys cannot be used together with the lights section"));
+            lights
+        }
+        (Some(lights), None) => lights,
+        (None, _) => {
+            ambient_color.get_or_insert(light_color.scale(0.2));
+            vec![Light::point(light_position, light_intensity, get_color("white"))]
+        }
+    };
+    lights.extend(emitters.into_iter().map(Light::emitter));
 
+    if !image_size_given {
+        errors.push(SceneError::new(file_path, 0, "", "Missing '$$$ image_size'"));
+    }
 
+    // Un groupe invalide peut être signalé une fois par groupe qui l'instancie
+    if !errors.is_empty() {
+        errors.sort_by_key(|error| error.line);
+        errors.dedup();
+        return Err(errors);
     }
 
-    SceneParams {
+    Ok(SceneParams {
         image_size,
         background_color,
         camera: Camera::new(camera_position, camera_look_at, camera_up, camera_fov, camera_aspect_ratio),
-        lights: vec![Light {
-            position: light_position,
-            intensity: light_intensity,
-            color: light_color,
-        }],
+        samples_per_pixel,
+        pixel_filter,
+        integrator,
+        ambient_color: ambient_color.unwrap_or(get_color("black")),
+        lights,
+        bvh: Bvh::build(&objects),
         objects,
+    })
+}
+
+// Sections de plusieurs lignes du fichier de configuration
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Section {
+    None,
+    Shapes,
+    Materials,
+    Lights,
+    Group(usize), // Indice dans la liste des définitions de groupes
+    Csg(usize),
+    Sdf(usize),
+}
+
+impl Section {
+    fn end_marker(&self) -> &'static str {
+        match self {
+            Section::Shapes => "$$$ end_shape",
+            Section::Materials => "$$$ end_materials",
+            Section::Lights => "$$$ end_lights",
+            Section::Group(_) => "$$$ end_group",
+            Section::Csg(_) => "$$$ end_csg",
+            Section::Sdf(_) => "$$$ end_sdf",
+            Section::None => "",
+        }
+    }
+}
+
+// Section "$$$ group nom", "$$$ csg nom opération" ou "$$$ sdf nom" : ses lignes sont lues une fois tout le fichier parcouru
+struct GroupDefinition {
+    name: String,
+    header: usize,      // Indice de la ligne d'en-tête de la section
+    operation: Option<CsgOperation>, // Opération qui combine les formes d'une section csg
+    lines: Vec<usize>,  // Indices des lignes de formes du groupe
+}
+
+// Données nécessaires à la construction des groupes, une fois tout le fichier lu
+struct GroupContext<'a> {
+    definitions: &'a [GroupDefinition],
+    lines: &'a [String],
+    materials: &'a HashMap<String, Material>,
+    sdfs: &'a HashMap<String, Arc<SdfNode>>,
+    directory: &'a Path,
+    error_at: &'a dyn Fn(usize, &str) -> SceneError,
+}
+
+impl GroupContext<'_> {
+    // Construit le groupe d'indice `index`, après les groupes qu'il instancie.
+    // `pending` contient les groupes en cours de construction, pour détecter les cycles.
+    fn build(&self, index: usize, groups: &mut HashMap<String, Arc<Group>>, pending: &mut Vec<usize>) -> Result<(), SceneError> {
+        let definition = &self.definitions[index];
+        if groups.contains_key(&definition.name) {
+            return Ok(());
+        }
+        pending.push(index);
+
+        for &line_index in &definition.lines {
+            let parts: Vec<&str> = self.lines[line_index].split('/').map(|part| part.trim()).collect();
+            if parts[0] != "instance" || parts.len() < 2 {
+                continue;
+            }
+            if let Some(dependency) = self.definitions.iter().position(|d| d.name == parts[1]) {
+                if pending.contains(&dependency) {
+                    return Err((self.error_at)(line_index, &format!("Group '{}' contains itself", parts[1])));
+                }
+                self.build(dependency, groups, pending)?;
+            }
+        }
+
+        let mut objects = Vec::new();
+        for &line_index in &definition.lines {
+            let object = parse_shape(self.lines[line_index].trim(), self.materials, groups, self.sdfs, self.directory)
+                .map_err(|e| (self.error_at)(line_index, &format!("Invalid shape: {}", e)))?;
+            if definition.operation.is_some() && !object.is_solid() {
+                return Err((self.error_at)(line_index, "Only closed solids can be combined by a CSG operation (a mesh must be closed: each edge shared by exactly two faces)"));
+            }
+            objects.push(object);
+        }
+
+        // Une section csg combine ses formes deux à deux, de la première à la dernière : ((a op b) op c)...
+        if let Some(operation) = definition.operation {
+            if objects.len() < 2 {
+                return Err((self.error_at)(definition.header, "A CSG operation needs at least two shapes"));
+            }
+            let mut objects_iter = objects.into_iter();
+            let first = objects_iter.next().unwrap();
+            let combined = objects_iter.fold(first, |left, right| Object::Csg(Box::new(Csg::new(operation, left, right))));
+            objects = vec![combined];
+        }
+
+        let group = Group::new(&definition.name, objects).map_err(|e| (self.error_at)(definition.header, &e))?;
+
+        pending.pop();
+        groups.insert(definition.name.clone(), Arc::new(group));
+        Ok(())
+    }
+}
+
+// Lit une ligne de la section lights : "type/couleur/(x,y,z)" suivie de paramètres optionnels (intensity, ...).
+// Le vecteur est la position de la lumière, ou sa direction pour une lumière directionnelle.
+pub fn parse_light(line: &str) -> Result<Light, String> {
+    let parts: Vec<&str> = line.split('/').map(|part| part.trim()).collect();
+    if parts.len() < 3 {
+        return Err("Expected 'type/color/(x,y,z)'".to_string());
+    }
+
+    let params = Params::parse(&parts[3..])?;
+    Light::new(parts[0], parse_color(parts[1])?, parse_vec3(parts[2])?, &params)
+}
+
+// Lit une ligne de la section shapes : "type/couleur/(x,y,z)" suivie de paramètres optionnels.
+// La couleur peut être remplacée par le nom d'un matériau de la section materials.
+pub fn parse_shape(
+    line: &str,
+    materials: &HashMap<String, Material>,
+    groups: &HashMap<String, Arc<Group>>,
+    sdfs: &HashMap<String, Arc<SdfNode>>,
+    directory: &Path,
+) -> Result<Object, String> {
+    let shape_parts: Vec<&str> = line.split('/').map(|part| part.trim()).collect();
+    if shape_parts.len() < 3 {
+        return Err("Expected 'type/color/(x,y,z)'".to_string());
+    }
+
+    let params = Params::parse(&shape_parts[3..])?;
+    let location = parse_vec3(shape_parts[2])?;
+
+    // Une instance indique un nom de groupe à la place de la couleur
+    if shape_parts[0] == "instance" {
+        let group = groups.get(shape_parts[1]).ok_or_else(|| format!("Unknown group '{}'", shape_parts[1]))?;
+        return group::instance(group, location, &params);
+    }
+    let material = resolve_material(shape_parts[1], materials)?;
+
+    // Objet défini par une section sdf : "sdf/matériau/(x,y,z)/shape=nom"
+    if shape_parts[0] == "sdf" {
+        let mut params = params;
+        let transform = Transform::from_params(&params.split_off(&TRANSFORM_PARAMS))?;
+        params.check(&["shape"], "sdf")?;
+        let name = params.get("shape").ok_or("Missing parameter 'shape'")?;
+        let root = sdfs.get(name).ok_or_else(|| format!("Unknown sdf '{}'", name))?;
+        return Ok(Object::Sdf(Box::new(Sdf::new(Arc::clone(root), location, material))).with_transform(transform, location));
+    }
+
+    Object::new(Shape { shape_type: shape_parts[0].to_string(), material, location, directory: directory.to_path_buf(), params })
+}
+
+// Un nom de matériau défini dans la configuration, sinon une couleur
+pub fn resolve_material(name: &str, materials: &HashMap<String, Material>) -> Result<Material, String> {
+    if let Some(material) = materials.get(name) {
+        return Ok(*material);
+    }
+    parse_color(name)
+        .map(Material::from_color)
+        .map_err(|_| format!("Unknown material or color '{}'", name))
+}
+
+// Lit une ligne de la section materials : "nom/clé=valeur/..."
+pub fn parse_material(line: &str) -> Result<(String, Material), String> {
+    let parts: Vec<&str> = line.split('/').map(|part| part.trim()).collect();
+    let name = parts[0];
+    if name.is_empty() || name.contains(char::is_whitespace) || name.contains('=') {
+        return Err("Expected 'name/key=value/...'".to_string());
+    }
+    let params = Params::parse(&parts[1..])?;
+    Ok((name.to_string(), Material::from_params(&params)?))
+}
+
+// Paramètres optionnels "clé=valeur" d'une ligne de configuration (ex: radius=5/color=red)
+#[derive(Debug, Clone, Default)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    pub fn parse(parts: &[&str]) -> Result<Params, String> {
+        let mut values: HashMap<String, String> = HashMap::new();
+        let mut last_key: Option<String> = None;
+        for part in parts {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = part.split_once('=') else {
+                // Un morceau sans "=" continue la valeur précédente : chemin de fichier contenant des "/"
+                match last_key.as_ref().and_then(|key| values.get_mut(key)) {
+                    Some(previous) => {
+                        previous.push('/');
+                        previous.push_str(part);
+                        continue;
+                    }
+                    None => return Err(format!("Expected 'key=value', found '{}'", part)),
+                }
+            };
+            let key = key.trim().to_lowercase();
+            if values.insert(key.clone(), value.trim().to_string()).is_some() {
+                return Err(format!("Parameter '{}' is given more than once", key));
+            }
+            last_key = Some(key);
+        }
+        Ok(Params { values })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|value| value.as_str())
+    }
+
+    // Lit un paramètre numérique, avec une valeur par défaut s'il est absent
+    pub fn number(&self, key: &str, default: f64) -> Result<f64, String> {
+        match self.get(key) {
+            Some(value) => value.parse::<f64>()
+                .map_err(|_| format!("Invalid value '{}' for parameter '{}'", value, key)),
+            None => Ok(default),
+        }
+    }
+
+    // Lit un paramètre numérique qui doit être strictement positif (dimensions)
+    pub fn positive(&self, key: &str, default: f64) -> Result<f64, String> {
+        let value = self.number(key, default)?;
+        if value > 0.0 {
+            Ok(value)
+        } else {
+            Err(format!("Parameter '{}' must be greater than 0, found {}", key, value))
+        }
+    }
+
+    // Lit un paramètre compris entre 0 et 1 (coefficients)
+    pub fn ratio(&self, key: &str, default: f64) -> Result<f64, String> {
+        let value = self.number(key, default)?;
+        if (0.0..=1.0).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!("Parameter '{}' must be between 0 and 1, found {}", key, value))
+        }
+    }
+
+    // Lit un nombre entier strictement positif (nombres d'échantillons)
+    pub fn count(&self, key: &str, default: u32) -> Result<u32, String> {
+        match self.get(key) {
+            None => Ok(default),
+            Some(value) => match value.parse::<u32>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(format!("Parameter '{}' must be a whole number greater than 0, found '{}'", key, value)),
+            },
+        }
+    }
+
+    pub fn color(&self, key: &str, default: Color) -> Result<Color, String> {
+        match self.get(key) {
+            Some(value) => parse_color(value).map_err(|e| format!("Invalid value for parameter '{}': {}", key, e)),
+            None => Ok(default),
+        }
+    }
+
+    pub fn vec3(&self, key: &str, default: Vec3) -> Result<Vec3, String> {
+        match self.get(key) {
+            Some(value) => parse_vec3(value).map_err(|e| format!("Invalid value for parameter '{}': {}", key, e)),
+            None => Ok(default),
+        }
+    }
+
+    // Retire les paramètres `keys` et les renvoie à part (paramètres communs à toutes les formes)
+    pub fn split_off(&mut self, keys: &[&str]) -> Params {
+        let values = keys.iter()
+            .filter_map(|key| self.values.remove_entry(*key))
+            .collect();
+        Params { values }
+    }
+
+    // Vérifie que tous les paramètres fournis sont connus pour `owner`
+    pub fn check(&self, allowed: &[&str], owner: &str) -> Result<(), String> {
+        let mut keys: Vec<&String> = self.values.keys().collect();
+        keys.sort();
+        for key in keys {
+            if !allowed.contains(&key.as_str()) {
+                return Err(format!("Unknown parameter '{}' for {}", key, owner));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -201,3 +654,49 @@ pub fn save_image_as_png(filename: &str, image: &[Vec<Color>]) {
 
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Écrit la configuration dans un fichier temporaire et la lit
+    fn parse(name: &str, config: &str) -> Result<SceneParams, Vec<SceneError>> {
+        let path = std::env::temp_dir().join(format!("rt_{}_{}.txt", name, std::process::id()));
+        std::fs::write(&path, config).unwrap();
+        let result = parse_config_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn reports_every_bad_line() {
+        let config = "\
+$$$ image_size :
+100 100
+
+$$$ background_color :
+gren
+
+$$$ shapes :
+sphere/white/(0,0,0)
+sphere/blak/(0,0,0)
+$$$ end_shape
+";
+        let Err(errors) = parse("two_errors", config) else { panic!("expected errors") };
+        let lines: Vec<(usize, &str)> = errors.iter().map(|error| (error.line, error.text.as_str())).collect();
+        assert_eq!(lines, vec![(5, "gren"), (9, "sphere/blak/(0,0,0)")]);
+        assert_eq!(errors[1].reason, "Invalid shape: Unknown material or color 'blak'");
+    }
+
+    #[test]
+    fn key_after_unterminated_section_is_still_read() {
+        let config = "\
+$$$ materials :
+matte/diffuse=grey
+$$$ image_size :
+0 100
+";
+        let Err(errors) = parse("unterminated", config) else { panic!("expected errors") };
+        let lines: Vec<usize> = errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![3, 4]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{Add, Sub, Mul, Div};
+use std::ops::{Add, Sub, Mul, Div, Neg};
 
 //Module pour la gestion des vecteurs 3D
 
@@ -55,6 +55,11 @@ impl Vec3 {
         }
     }
 
+    // Produit composante par composante (le produit `*` entre deux Vec3 est le produit scalaire)
+    pub fn mul_components(&self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
     pub fn inverse(&self) -> Vec3 {
         Vec3 {
             x: 1.0 / self.x,
@@ -63,9 +68,28 @@ impl Vec3 {
         }
     }
 
+    // Deux directions unitaires perpendiculaires entre elles et au vecteur (unitaire), formant un repère direct
+    pub fn orthonormal_basis(&self) -> (Vec3, Vec3) {
+        let reference = if self.x.abs() < 0.9 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 0.0, 1.0) };
+        let first = (reference - *self * self.dot(reference)).normalize();
+        (first, self.cross(first))
+    }
+
     pub fn reflect(&self, normal: Vec3) -> Vec3 {
         *self - 2.0 * self.dot(normal) * normal
     }
+
+    // Direction réfractée (loi de Snell-Descartes) pour une direction unitaire et une normale tournée vers elle.
+    // `eta` est le rapport n1 / n2 ; renvoie None en cas de réflexion totale interne.
+    pub fn refract(&self, normal: Vec3, eta: f64) -> Option<Vec3> {
+        let cos_i = -self.dot(normal);
+        let sin2_t = eta * eta * (1.0 - cos_i * cos_i);
+        if sin2_t > 1.0 {
+            return None;
+        }
+        let cos_t = (1.0 - sin2_t).sqrt();
+        Some((eta * *self + (eta * cos_i - cos_t) * normal).normalize())
+    }
 }
 
 impl Add for Vec3 {
@@ -84,6 +108,14 @@ impl Sub for Vec3 {
     }
 }
 
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
 impl Mul<f64> for Vec3 {
     type Output = Vec3;
 
@@ -134,20 +166,20 @@ impl Div<Vec3> for Vec3 {
 }
 
 
-pub fn parse_vec3(value: &str) -> Vec3 {
-    let cleaned_value = value.trim_matches(|p| p == '(' || p == ')');
+pub fn parse_vec3(value: &str) -> Result<Vec3, String> {
+    let cleaned_value = value.trim().trim_matches(|p| p == '(' || p == ')');
         let parts: Vec<&str> = cleaned_value.split(',')
                                             .map(|v| v.trim())
                                             .collect();
 
 
         if parts.len() != 3 {
-            panic!("Invalid Vec3 format: expected 3 coordinates, found {}, for {}", parts.len(), value);
+            return Err(format!("Invalid Vec3 format: expected 3 coordinates, found {}, for {}", parts.len(), value));
         }
 
-        let x: f64 = parts[0].parse().expect("Failed to parse x coordinate as f64");
-        let y: f64 = parts[1].parse().expect("Failed to parse y coordinate as f64");
-        let z: f64 = parts[2].parse().expect("Failed to parse z coordinate as f64");
+        let x: f64 = parts[0].parse().map_err(|_| format!("Failed to parse x coordinate '{}' as f64", parts[0]))?;
+        let y: f64 = parts[1].parse().map_err(|_| format!("Failed to parse y coordinate '{}' as f64", parts[1]))?;
+        let z: f64 = parts[2].parse().map_err(|_| format!("Failed to parse z coordinate '{}' as f64", parts[2]))?;
 
-        Vec3 { x, y, z }
+        Ok(Vec3 { x, y, z })
 }